@@ -0,0 +1,141 @@
+//! Fluent construction of fake DPI windows.
+
+use crate::{CoordinateSpace, DpiError, FakeDpiConfig, FakeDpiWindow, RoundingMode};
+
+/// Builds a fake DPI window around an already built window.
+///
+/// Create one with `FakeDpiWindow::builder`, chain the options and call `build`.
+/// Like `FakeDpiWindow::new`, this does not resize the inner window.
+#[derive(Clone, Debug)]
+pub struct FakeDpiWindowBuilder<W> {
+    inner: W,
+    config: FakeDpiConfig,
+    enabled: bool,
+    scale_relative_motion: bool,
+    check_size_ratio: bool,
+    cursor_space: CoordinateSpace,
+    clamp_cursor: bool,
+}
+
+impl<W> FakeDpiWindowBuilder<W> {
+    /// Creates a new builder with default options.
+    pub fn new(inner: W) -> FakeDpiWindowBuilder<W> {
+        FakeDpiWindowBuilder {
+            inner,
+            config: FakeDpiConfig::new(),
+            enabled: true,
+            scale_relative_motion: true,
+            check_size_ratio: false,
+            cursor_space: CoordinateSpace::Logical,
+            clamp_cursor: false,
+        }
+    }
+
+    /// Sets the DPI factor for both axes.
+    pub fn dpi(self, dpi: f64) -> Self {self.dpi_xy(dpi, dpi)}
+
+    /// Sets the horizontal and vertical DPI factors.
+    pub fn dpi_xy(mut self, dpi_x: f64, dpi_y: f64) -> Self {
+        self.config.dpi_x = dpi_x;
+        self.config.dpi_y = dpi_y;
+        self
+    }
+
+    /// Sets the minimum and maximum DPI factor.
+    pub fn dpi_range(mut self, min: f64, max: f64) -> Self {
+        self.config.min_dpi = min;
+        self.config.max_dpi = max;
+        self
+    }
+
+    /// Sets the rounding mode for logical coordinates.
+    pub fn rounding(mut self, val: RoundingMode) -> Self {
+        self.config.rounding = val;
+        self
+    }
+
+    /// Sets whether mouse scroll is scaled by the DPI factor.
+    pub fn scale_scroll(mut self, val: bool) -> Self {
+        self.config.scale_scroll = val;
+        self
+    }
+
+    /// Sets whether relative mouse motion is scaled by the DPI factor.
+    pub fn scale_relative_motion(mut self, val: bool) -> Self {
+        self.scale_relative_motion = val;
+        self
+    }
+
+    /// Sets whether the scaling is enabled.
+    pub fn enabled(mut self, val: bool) -> Self {
+        self.enabled = val;
+        self
+    }
+
+    /// Sets whether resize events from the inner window are checked for mismatched sizes.
+    pub fn check_size_ratio(mut self, val: bool) -> Self {
+        self.check_size_ratio = val;
+        self
+    }
+
+    /// Sets the coordinate space of pointer positions.
+    pub fn cursor_space(mut self, val: CoordinateSpace) -> Self {
+        self.cursor_space = val;
+        self
+    }
+
+    /// Sets whether mouse cursor positions are clamped to the window bounds.
+    pub fn clamp_cursor(mut self, val: bool) -> Self {
+        self.clamp_cursor = val;
+        self
+    }
+
+    /// Builds the window.
+    ///
+    /// Returns an error if any DPI factor or limit is zero, negative or not finite,
+    /// or if the minimum DPI factor is greater than the maximum.
+    pub fn build(self) -> Result<FakeDpiWindow<W>, DpiError> {
+        let mut w = FakeDpiWindow::from_config(self.inner, self.config)?;
+        w.enabled = self.enabled;
+        w.scale_relative_motion = self.scale_relative_motion;
+        w.check_size_ratio = self.check_size_ratio;
+        w.cursor_space = self.cursor_space;
+        w.clamp_cursor = self.clamp_cursor;
+        Ok(w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockWindow;
+    use crate::InvalidDpi;
+    use input::{Event, Input, Motion};
+    use window::{Size, Window};
+
+    #[test]
+    fn options() {
+        let mut w = FakeDpiWindow::builder(MockWindow::new([600, 600]))
+            .dpi(1.5)
+            .rounding(RoundingMode::Round)
+            .clamp_cursor(true)
+            .scale_scroll(true)
+            .build()
+            .unwrap();
+        assert_eq!(w.dpi(), 1.5);
+        assert_eq!(w.get_rounding(), RoundingMode::Round);
+        assert!(w.get_clamp_cursor());
+        assert!(w.get_scale_scroll());
+        assert!(w.get_enabled());
+        assert_eq!(w.size(), Size {width: 400.0, height: 400.0});
+        w.inner.push_input(Input::Move(Motion::MouseCursor([1000.0, 301.0])));
+        assert_eq!(w.poll_event(), Some(Event::Input(Input::Move(Motion::MouseCursor([400.0, 201.0])), None)));
+    }
+
+    #[test]
+    fn invalid() {
+        let builder = FakeDpiWindow::builder(MockWindow::new([600, 600]));
+        assert_eq!(builder.clone().dpi(0.0).build().err(), Some(DpiError::Invalid(InvalidDpi(0.0))));
+        assert_eq!(builder.dpi_range(4.0, 1.0).build().err(), Some(DpiError::InvertedRange {min: 4.0, max: 1.0}));
+    }
+}
@@ -0,0 +1,245 @@
+//! Configuration of fake DPI settings.
+
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "serde")]
+use std::{error::Error, fmt, fs, io, path::{Path, PathBuf}};
+
+#[cfg(feature = "serde")]
+use crate::DpiError;
+use crate::{RoundingMode, DEFAULT_DPI, DEFAULT_MAX_DPI, DEFAULT_MIN_DPI};
+
+/// Stores the configuration of a fake DPI window.
+///
+/// With the `serde` feature, this can be serialized and deserialized,
+/// e.g. to keep a matrix of test scenarios in configuration files.
+///
+/// Equality and hashing compare the bits of the float fields exactly,
+/// such that configurations can be deduplicated and used as map keys.
+/// This means that `0.0` and `-0.0` differ, while `NaN` equals itself,
+/// and factors that only differ by a rounding error are distinct.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FakeDpiConfig {
+    /// The horizontal DPI factor.
+    pub dpi_x: f64,
+    /// The vertical DPI factor.
+    pub dpi_y: f64,
+    /// The minimum DPI factor.
+    pub min_dpi: f64,
+    /// The maximum DPI factor.
+    pub max_dpi: f64,
+    /// The rounding mode for logical coordinates.
+    pub rounding: RoundingMode,
+    /// Whether mouse scroll is scaled by the DPI factor.
+    pub scale_scroll: bool,
+}
+
+impl FakeDpiConfig {
+    /// Creates a new configuration with default values.
+    pub fn new() -> FakeDpiConfig {
+        FakeDpiConfig {
+            dpi_x: DEFAULT_DPI,
+            dpi_y: DEFAULT_DPI,
+            min_dpi: DEFAULT_MIN_DPI,
+            max_dpi: DEFAULT_MAX_DPI,
+            rounding: RoundingMode::None,
+            scale_scroll: false,
+        }
+    }
+
+    /// Reads a configuration from a file, with the format inferred from the extension.
+    ///
+    /// Files ending with `.toml` are read as TOML, and files ending with `.json` as JSON.
+    /// Missing fields use the defaults. The values are not validated,
+    /// which happens when the configuration is applied.
+    #[cfg(feature = "serde")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<FakeDpiConfig, ConfigFileError> {
+        let path = path.as_ref();
+        match ConfigFormat::from_path(path) {
+            Some(format) => FakeDpiConfig::from_file_with_format(path, format),
+            None => Err(ConfigFileError::UnknownFormat(path.to_path_buf())),
+        }
+    }
+
+    /// Reads a configuration from a file in the given format.
+    #[cfg(feature = "serde")]
+    pub fn from_file_with_format<P: AsRef<Path>>(
+        path: P,
+        format: ConfigFormat
+    ) -> Result<FakeDpiConfig, ConfigFileError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(ConfigFileError::Io)?;
+        let parsed = match format {
+            ConfigFormat::Toml => toml::from_str(&text).map_err(|err| err.to_string()),
+            ConfigFormat::Json => serde_json::from_str(&text).map_err(|err| err.to_string()),
+        };
+        parsed.map_err(|message| ConfigFileError::Parse {path: path.to_path_buf(), message})
+    }
+
+    /// Returns the fields with floats as bits, for comparing and hashing.
+    fn key(&self) -> ([u64; 4], RoundingMode, bool) {
+        let floats = [self.dpi_x, self.dpi_y, self.min_dpi, self.max_dpi].map(f64::to_bits);
+        (floats, self.rounding, self.scale_scroll)
+    }
+}
+
+impl Default for FakeDpiConfig {
+    fn default() -> FakeDpiConfig {FakeDpiConfig::new()}
+}
+
+/// The format of a configuration file.
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigFormat {
+    /// TOML, e.g. `dpi_x = 1.5`.
+    Toml,
+    /// JSON, e.g. `{"dpi_x": 1.5}`.
+    Json,
+}
+
+#[cfg(feature = "serde")]
+impl ConfigFormat {
+    /// Infers the format from the extension of a path, ignoring case.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<ConfigFormat> {
+        let ext = path.as_ref().extension()?.to_str()?;
+        if ext.eq_ignore_ascii_case("toml") {
+            Some(ConfigFormat::Toml)
+        } else if ext.eq_ignore_ascii_case("json") {
+            Some(ConfigFormat::Json)
+        } else {
+            None
+        }
+    }
+}
+
+/// An error from loading a configuration file.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum ConfigFileError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The format could not be inferred from the extension.
+    UnknownFormat(PathBuf),
+    /// The file could not be parsed.
+    Parse {
+        /// The path of the file.
+        path: PathBuf,
+        /// The message of the parser.
+        message: String,
+    },
+    /// The configuration has an invalid DPI factor or range.
+    Invalid(DpiError),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFileError::Io(err) => write!(f, "Could not read config file: {}", err),
+            ConfigFileError::UnknownFormat(path) =>
+                write!(f, "Unknown config file format of `{}`, expected `.toml` or `.json`", path.display()),
+            ConfigFileError::Parse {path, message} =>
+                write!(f, "Could not parse config file `{}`: {}", path.display(), message),
+            ConfigFileError::Invalid(err) => write!(f, "Invalid config file: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Error for ConfigFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigFileError::Io(err) => Some(err),
+            ConfigFileError::Invalid(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for FakeDpiConfig {
+    fn eq(&self, other: &FakeDpiConfig) -> bool {self.key() == other.key()}
+}
+
+impl Eq for FakeDpiConfig {}
+
+impl Hash for FakeDpiConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {self.key().hash(state)}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(config: &FakeDpiConfig) -> u64 {
+        use std::collections::hash_map::DefaultHasher;
+
+        let mut hasher = DefaultHasher::new();
+        config.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn eq_and_hash() {
+        let a = FakeDpiConfig {dpi_x: 1.5, rounding: RoundingMode::Round, ..FakeDpiConfig::new()};
+        let b = FakeDpiConfig {rounding: RoundingMode::Round, dpi_x: 1.5, ..FakeDpiConfig::default()};
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+        assert_ne!(a, FakeDpiConfig::new());
+        assert_ne!(FakeDpiConfig {dpi_x: 0.0, ..a.clone()}, FakeDpiConfig {dpi_x: -0.0, ..a.clone()});
+        let nan = FakeDpiConfig {dpi_x: f64::NAN, ..a.clone()};
+        assert_eq!(nan, nan.clone());
+        let set: std::collections::HashSet<_> = vec![a, b, nan].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn round_trip() {
+        let config = FakeDpiConfig {
+            dpi_x: 1.5,
+            dpi_y: 1.25,
+            min_dpi: 1.0,
+            max_dpi: 4.0,
+            rounding: RoundingMode::Floor,
+            scale_scroll: true,
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<FakeDpiConfig>(&json).unwrap(), config);
+    }
+
+    #[cfg(feature = "serde")]
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn from_file() {
+        let expected = FakeDpiConfig {
+            dpi_x: 1.5,
+            dpi_y: 1.25,
+            max_dpi: 4.0,
+            rounding: RoundingMode::Floor,
+            ..FakeDpiConfig::new()
+        };
+        assert_eq!(FakeDpiConfig::from_file(fixture("scenario.toml")).unwrap(), expected);
+        assert_eq!(FakeDpiConfig::from_file(fixture("scenario.json")).unwrap(), expected);
+        assert_eq!(ConfigFormat::from_path("a/b.TOML"), Some(ConfigFormat::Toml));
+
+        assert!(matches!(FakeDpiConfig::from_file("scenario.yaml"), Err(ConfigFileError::UnknownFormat(_))));
+        assert!(matches!(FakeDpiConfig::from_file(fixture("missing.toml")), Err(ConfigFileError::Io(_))));
+        let err = FakeDpiConfig::from_file_with_format(fixture("scenario.json"), ConfigFormat::Toml).unwrap_err();
+        assert!(matches!(err, ConfigFileError::Parse {..}));
+        assert!(err.to_string().contains("scenario.json"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn missing_fields_use_defaults() {
+        let config: FakeDpiConfig = serde_json::from_str(r#"{"dpi_x": 3.0}"#).unwrap();
+        assert_eq!(config.dpi_x, 3.0);
+        assert_eq!(config.dpi_y, DEFAULT_DPI);
+        assert_eq!(config.rounding, RoundingMode::None);
+    }
+}
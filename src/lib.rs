@@ -4,112 +4,6404 @@
 //! A window wrapper that simulates fake Hi-DPI screen by manipulating window events.
 //!
 //! Used to test application logic on computers without Hi-DPI screen support.
+//!
+//! ## Migrating from 0.6
+//!
+//! The public `dpi` field is replaced by separate horizontal and vertical DPI factors,
+//! which are validated and clamped to the DPI range.
+//! Read the factor with `dpi()` instead of `w.dpi`, and set it with `set_dpi(dpi)`
+//! instead of assigning `w.dpi = dpi`, which also emits a resize event with the new logical size.
+//! Use `dpi_x()`, `dpi_y()` and `set_dpi_xy` for separate factors.
 
 extern crate window;
 extern crate input;
 
-use std::time::Duration;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 use std::error::Error;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Deref, DerefMut};
+use std::sync::mpsc::Sender;
 
 use window::{
     AdvancedWindow,
     BuildFromWindowSettings,
+    OpenGLWindow,
     Position,
+    ProcAddress,
     Window,
     WindowSettings,
     Size,
 };
-use input::{Input, Event};
+use input::{Button, ButtonArgs, ButtonState, Input, Event, GenericEvent, Key, ResizeArgs, TimeStamp};
+
+pub use builder::FakeDpiWindowBuilder;
+pub use config::FakeDpiConfig;
+#[cfg(feature = "serde")]
+pub use config::{ConfigFileError, ConfigFormat};
+pub use settings::{BuildSizeMode, FakeDpiSettings, FakeDpiWindowSettings, InvalidDpiEnvVar, DPI_ENV_VAR};
+pub use state::State;
+#[cfg(feature = "headless")]
+pub use mock::MockWindow;
+
+mod builder;
+mod config;
+#[cfg(feature = "recording")]
+mod recording;
+mod settings;
+mod state;
+#[cfg(any(test, feature = "headless"))]
+mod mock;
+#[cfg(feature = "test-support")]
+mod test_support;
+
+/// The default DPI factor.
+const DEFAULT_DPI: f64 = 2.0;
+/// The default DPI presets.
+const DEFAULT_DPI_PRESETS: [f64; 5] = [1.0, 1.25, 1.5, 2.0, 3.0];
+/// The default minimum DPI factor.
+const DEFAULT_MIN_DPI: f64 = 0.25;
+/// The default maximum DPI factor.
+const DEFAULT_MAX_DPI: f64 = 8.0;
+/// The default tolerance for comparing coordinates against the window bounds.
+const DEFAULT_EPSILON: f64 = 1e-9;
 
 /// Wraps a window to simulate Hi-DPI screen.
+///
+/// Cloning copies the inner window and the configuration,
+/// but not callbacks and loggers, which are reset on the clone.
+///
+/// Fake DPI windows can be stacked with `stack`, which multiplies the DPI factors
+/// for the window size, `set_size`, mapped events and the draw size,
+/// e.g. `2.0` over `1.5` has the effective factor `3.0`.
+/// Wrapping a fake DPI window with `new` instead treats it like any inner window,
+/// whose draw size is ignored, so only the outermost factor applies to the draw size.
+#[derive(Clone)]
 pub struct FakeDpiWindow<W> {
     /// The inner window.
     pub inner: W,
-    /// Controls DPI factor.
-    ///
-    /// This can be changed at run-time to test application logic.
-    /// By default, this is set to `2.0`.
-    pub dpi: f64,
+    dpi_x: Dpi,
+    dpi_y: Dpi,
+    /// The reciprocal DPI factors, to multiply instead of divide per event.
+    inv_dpi: [f64; 2],
+    input_dpi: Option<f64>,
+    min_dpi: f64,
+    max_dpi: f64,
+    resize_pending: bool,
+    draw_size_offset: Option<[i32; 2]>,
+    resize_throttle: Duration,
+    resize_defer: u32,
+    /// The number of polls left before a deferred resize event is emitted.
+    deferred_polls: u32,
+    resize_debounce: Duration,
+    /// The physical size reported while debouncing resize events.
+    settled_size: Option<Size>,
+    /// The latest resize event of the inner window held back while debouncing, with its arrival.
+    held_resize: Option<(Instant, ResizeArgs)>,
+    input_latency: Duration,
+    dpi_threshold: Option<Size>,
+    below_threshold: bool,
+    compensate_real_dpi: bool,
+    /// Whether the inner window is a fake DPI layer, whose ratio of draw size to size is kept.
+    stacked: bool,
+    /// The real DPI factors of the inner window, from the ratio of its draw size to its size.
+    real_dpi: [f64; 2],
+    /// Events from the inner window with their arrival time, held back for the input latency.
+    delayed: VecDeque<(Instant, Event)>,
+    last_resize: Option<Instant>,
+    last_size: Option<Size>,
+    enabled: bool,
+    scale_scroll: bool,
+    scale_relative_motion: bool,
+    scale_mask: ScaleMask,
+    cursor_captured: bool,
+    visible: bool,
+    check_size_ratio: bool,
+    rounding: RoundingMode,
+    cursor_space: CoordinateSpace,
+    clamp_cursor: bool,
+    epsilon: f64,
+    flip_y: bool,
+    orientation: Orientation,
+    report_physical_size: bool,
+    strict: bool,
+    size_check_tolerance: Option<f64>,
+    origin: [f64; 2],
+    letterbox: Option<Size>,
+    paired_scroll: Option<f64>,
+    scroll_step: Option<f64>,
+    derive_relative_motion: bool,
+    event_stats: Option<EventStats>,
+    dpi_hotkey: Option<Key>,
+    dpi_hotkey_step: f64,
+    swallow_dpi_hotkey: bool,
+    auto_close_after: Option<u32>,
+    frames: u32,
+    hooks: Hooks,
+    /// Updates the DPI suffix of the title, if enabled.
+    retag_title: Option<fn(&mut W, f64)>,
+    monitors: Vec<f64>,
+    active_monitor: Option<usize>,
+    monitor_layout: Vec<Monitor>,
+    virtual_desktop: bool,
+    /// The position passed to `set_position`, with the physical position forwarded to the inner window.
+    window_position: Option<(Position, Position)>,
+    dpi_presets: Vec<f64>,
+    /// The index of the preset applied by `cycle_dpi_preset`, with the resulting DPI factor.
+    last_preset: Option<(usize, f64)>,
+    dpi_steps: Vec<f64>,
+    injected: VecDeque<Input>,
+    paused: bool,
+    /// Events from the inner window that were polled early, by `has_pending_events` or while paused.
+    buffered: VecDeque<Event>,
+    paired: Option<Event>,
+    scroll_accum: [f64; 2],
+    /// The previous mapped cursor position, for deriving relative motion.
+    prev_cursor: Option<[f64; 2]>,
+    last_event: Option<Input>,
+    last_cursor: Option<[f64; 2]>,
+    last_resize_mapping: Option<(ResizeArgs, ResizeArgs)>,
+    last_resize_synthetic: Option<bool>,
+    cursor_quantum: Option<f64>,
+    input_precision: Option<f64>,
+    /// Whether the event being delivered was generated by the wrapper.
+    synthetic: bool,
+    /// Whether a `SuppressSyntheticEvents` guard is alive.
+    suppress_synthetic: bool,
+    /// The event returned by `peek_event` before and after mapping, already delivered,
+    /// that polling returns next.
+    peeked: Option<(Event, Event)>,
+    #[cfg(feature = "recording")]
+    replay: VecDeque<Event>,
+    animation: Option<DpiAnimation>,
+    /// Shared by `randomize_dpi` and the DPI jitter, which draws when reading the factor.
+    #[cfg(feature = "random")]
+    dpi_rng: std::cell::RefCell<rand::rngs::Xoshiro256PlusPlus>,
+    #[cfg(feature = "random")]
+    dpi_jitter: f64,
 }
 
-impl<W: BuildFromWindowSettings> BuildFromWindowSettings for FakeDpiWindow<W> {
-    fn build_from_window_settings(
-        settings: &WindowSettings
-    ) ->  Result<Self, Box<dyn Error + 'static>> {
-        let dpi = 2.0;
-        let mut settings = settings.clone();
-        let size = settings.get_size();
-        settings.set_size(Size {
-            width: size.width * dpi,
-            height: size.height * dpi,
-        });
-        Ok(FakeDpiWindow {
-            inner: settings.build()?,
-            dpi,
+/// Controls how logical coordinates are rounded after scaling.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RoundingMode {
+    /// Keep fractional coordinates.
+    #[default]
+    None,
+    /// Round to the nearest integer, with halfway cases away from zero.
+    Round,
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceil,
+    /// Round towards zero.
+    Truncate,
+}
+
+/// The coordinate space of pointer positions returned by the wrapper.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CoordinateSpace {
+    /// Logical units, matching the window size.
+    #[default]
+    Logical,
+    /// Physical pixels, matching the draw size.
+    Physical,
+}
+
+impl RoundingMode {
+    /// Rounds a value.
+    pub fn apply(self, val: f64) -> f64 {
+        match self {
+            RoundingMode::None => val,
+            RoundingMode::Round => val.round(),
+            RoundingMode::Floor => val.floor(),
+            RoundingMode::Ceil => val.ceil(),
+            RoundingMode::Truncate => val.trunc(),
+        }
+    }
+}
+
+/// A logger that receives the physical and logical form of an event.
+pub type EventLogger = Box<dyn FnMut(&Input, &Input)>;
+
+/// A transform that rewrites logical input events.
+pub type EventTransform = Box<dyn FnMut(Input) -> Input>;
+
+/// A callback that receives the physical arguments of a mismatched resize event.
+pub type SizeMismatchCallback = Box<dyn FnMut(&ResizeArgs)>;
+
+/// A callback that receives a size passed to `set_size` that looks physical, and the current logical size.
+pub type SuspiciousSizeCallback = Box<dyn FnMut(Size, Size)>;
+
+/// A hook that receives the logical window size and the draw size on each buffer swap.
+pub type CaptureHook = Box<dyn FnMut(Size, Size)>;
+
+/// Maps pointer input and sizes from physical to logical space, replacing the DPI math.
+///
+/// Each method receives the horizontal and vertical factor that the built-in mapping
+/// would divide by. The default methods divide by them without rounding,
+/// except for touch positions, which are normalized and kept as they are.
+/// Implement only the methods that should behave differently, e.g. to simulate lens distortion.
+pub trait CoordinateMapper {
+    /// Maps a mouse cursor position.
+    fn map_cursor(&self, pos: [f64; 2], dpi: [f64; 2]) -> [f64; 2] {divide(pos, dpi)}
+    /// Maps relative mouse motion.
+    fn map_relative(&self, d: [f64; 2], dpi: [f64; 2]) -> [f64; 2] {divide(d, dpi)}
+    /// Maps a mouse scroll delta.
+    fn map_scroll(&self, d: [f64; 2], dpi: [f64; 2]) -> [f64; 2] {divide(d, dpi)}
+    /// Maps a touch event.
+    fn map_touch(&self, args: input::TouchArgs, _dpi: [f64; 2]) -> input::TouchArgs {args}
+    /// Maps the window size, both for `size()` and resize events.
+    fn map_size(&self, size: Size, dpi: [f64; 2]) -> Size {divide(size.into(), dpi).into()}
+}
+
+/// Divides a point by the DPI factors, multiplying with the reciprocals like the built-in mapping.
+fn divide(p: [f64; 2], dpi: [f64; 2]) -> [f64; 2] {scale_point(p, [1.0 / dpi[0], 1.0 / dpi[1]])}
+
+/// Multiplies a point by horizontal and vertical factors.
+fn scale_point(p: [f64; 2], factors: [f64; 2]) -> [f64; 2] {[p[0] * factors[0], p[1] * factors[1]]}
+
+/// Maps the coordinates of each input kind, the scaling core shared by the window and the free functions.
+///
+/// `scale_input` decides which events carry coordinates and dispatches them here,
+/// so all mapping agrees on what is scaled.
+/// Methods returning `None` pass the event through unchanged.
+trait Scaling {
+    /// Maps a mouse cursor position.
+    fn cursor(&self, pos: [f64; 2]) -> Option<[f64; 2]>;
+    /// Maps relative mouse motion.
+    fn relative(&self, d: [f64; 2]) -> Option<[f64; 2]>;
+    /// Maps a mouse scroll delta.
+    fn scroll(&self, _d: [f64; 2]) -> Option<[f64; 2]> {None}
+    /// Maps a touch event.
+    fn touch(&self, _args: input::TouchArgs) -> Option<input::TouchArgs> {None}
+    /// Maps the arguments of a resize event.
+    fn resize(&self, args: ResizeArgs) -> Option<ResizeArgs>;
+}
+
+/// Maps an input event with a scaling.
+fn scale_input<S: Scaling>(s: &S, e: Input) -> Input {
+    use Input::*;
+    use input::Motion::*;
+
+    let scaled = match e {
+        Move(MouseCursor(pos)) => s.cursor(pos).map(|pos| Move(MouseCursor(pos))),
+        Move(MouseRelative(d)) => s.relative(d).map(|d| Move(MouseRelative(d))),
+        Move(MouseScroll(d)) => s.scroll(d).map(|d| Move(MouseScroll(d))),
+        // Touch positions are normalized to `0.0..=1.0` by the input crate,
+        // so they are the same in logical and physical space unless mapped otherwise.
+        Move(Touch(args)) => s.touch(args).map(|args| Move(Touch(args))),
+        Resize(args) => s.resize(args).map(Resize),
+        // Controller axis positions are normalized device values, not pixels,
+        // so scaling them would change the stick sensitivity.
+        // File drag events only carry paths, without a cursor position.
+        // Cursor enter and leave events only carry a flag.
+        // Text events carry typed characters, which must reach the application as they are.
+        Focus(_) | Cursor(_) | Move(ControllerAxis(_)) | Button(_) | Text(_) | FileDrag(_) | Close(_) => None,
+    };
+    scaled.unwrap_or(e)
+}
+
+/// The scaling of the free mapping functions, by a single DPI factor without options.
+struct UniformScaling {
+    dpi: f64,
+    inv_dpi: f64,
+}
+
+impl UniformScaling {
+    /// Creates a scaling, panicking if the DPI factor is zero, negative or not finite.
+    fn new(dpi: f64) -> UniformScaling {
+        if let Err(err) = validate_dpi(dpi) {panic!("{}", err)}
+        UniformScaling {dpi, inv_dpi: 1.0 / dpi}
+    }
+}
+
+impl Scaling for UniformScaling {
+    fn cursor(&self, pos: [f64; 2]) -> Option<[f64; 2]> {Some(scale_point(pos, [self.inv_dpi; 2]))}
+    fn relative(&self, d: [f64; 2]) -> Option<[f64; 2]> {Some(scale_point(d, [self.inv_dpi; 2]))}
+    fn resize(&self, args: ResizeArgs) -> Option<ResizeArgs> {
+        let window_size = scale_point(args.window_size, [self.inv_dpi; 2]);
+        Some(ResizeArgs {
+            window_size,
+            draw_size: to_pixels(scale_point(window_size, [self.dpi; 2]).into()),
         })
     }
 }
 
-impl<W: Window> Window for FakeDpiWindow<W> {
-    fn set_should_close(&mut self, val: bool) {self.inner.set_should_close(val)}
-    fn should_close(&self) -> bool {self.inner.should_close()}
-    fn size(&self) -> Size {
-        let size = self.inner.size();
-        Size {width: size.width / self.dpi, height: size.height / self.dpi}
+/// Stores callbacks and loggers.
+#[derive(Default)]
+struct Hooks {
+    dpi_change_callback: Option<Box<dyn FnMut(f64)>>,
+    event_logger: Option<EventLogger>,
+    event_transform: Option<EventTransform>,
+    timestamp_transform: Option<Box<dyn FnMut(TimeStamp) -> TimeStamp>>,
+    frame_hook: Option<Box<dyn FnMut()>>,
+    capture_hook: Option<CaptureHook>,
+    set_size_hook: Option<Box<dyn FnMut(Size)>>,
+    size_mismatch_callback: Option<SizeMismatchCallback>,
+    suspicious_size_callback: Option<SuspiciousSizeCallback>,
+    coordinate_mapper: Option<Box<dyn CoordinateMapper>>,
+    event_channel: Option<Sender<(Input, Option<TimeStamp>)>>,
+    #[cfg(feature = "recording")]
+    recorder: Option<recording::Recorder>,
+}
+
+/// Hooks can not be cloned, so a clone starts without any.
+///
+/// A clone is also not recording events.
+impl Clone for Hooks {
+    fn clone(&self) -> Hooks {Hooks::default()}
+}
+
+/// Counts events mapped by a fake DPI window, by kind.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EventStats {
+    /// The number of mouse cursor events.
+    pub cursor: u64,
+    /// The number of relative mouse motion events.
+    pub relative: u64,
+    /// The number of mouse scroll events.
+    pub scroll: u64,
+    /// The number of touch events.
+    pub touch: u64,
+    /// The number of resize events.
+    pub resize: u64,
+    /// The number of other input events, which are passed through unchanged.
+    pub passthrough: u64,
+}
+
+impl EventStats {
+    /// Counts an input event.
+    fn count(&mut self, e: &Input) {
+        use input::Motion::*;
+
+        let n = match *e {
+            Input::Move(MouseCursor(_)) => &mut self.cursor,
+            Input::Move(MouseRelative(_)) => &mut self.relative,
+            Input::Move(MouseScroll(_)) => &mut self.scroll,
+            Input::Move(Touch(_)) => &mut self.touch,
+            Input::Resize(_) => &mut self.resize,
+            _ => &mut self.passthrough,
+        };
+        *n += 1;
     }
-    fn swap_buffers(&mut self) {self.inner.swap_buffers()}
-    fn wait_event(&mut self) -> Event {
-        map_input(self.dpi, self.inner.wait_event())
+}
+
+/// Stores the state of a DPI transition.
+#[derive(Clone)]
+struct DpiAnimation {
+    from: [f64; 2],
+    to: f64,
+    start: Instant,
+    duration: Duration,
+    easing: fn(f64) -> f64,
+}
+
+/// Linear easing function for DPI animations.
+pub fn ease_linear(t: f64) -> f64 {t}
+
+/// A DPI factor that is positive and finite.
+///
+/// The factor is validated on construction, so it can not be mixed up
+/// with an unvalidated number.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Dpi(f64);
+
+impl Dpi {
+    /// Creates a DPI factor.
+    ///
+    /// Returns an error if the DPI factor is zero, negative or not finite.
+    pub fn new(dpi: f64) -> Result<Dpi, InvalidDpi> {validate_dpi(dpi).map(Dpi)}
+
+    /// Returns the DPI factor as a number.
+    pub fn get(self) -> f64 {self.0}
+}
+
+impl Deref for Dpi {
+    type Target = f64;
+    fn deref(&self) -> &f64 {&self.0}
+}
+
+/// Creates a DPI factor, for constants and tests.
+///
+/// Use `Dpi::new` for factors that might be invalid.
+///
+/// # Panics
+///
+/// If the DPI factor is zero, negative or not finite.
+impl From<f64> for Dpi {
+    fn from(dpi: f64) -> Dpi {
+        Dpi::new(dpi).unwrap_or_else(|err| panic!("{}", err))
     }
-    fn wait_event_timeout(&mut self, val: Duration) -> Option<Event> {
-        self.inner.wait_event_timeout(val).map(|e| map_input(self.dpi, e))
+}
+
+impl From<Dpi> for f64 {
+    fn from(dpi: Dpi) -> f64 {dpi.0}
+}
+
+impl fmt::Display for Dpi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {self.0.fmt(f)}
+}
+
+/// An error returned when a DPI factor is zero, negative or not finite.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InvalidDpi(pub f64);
+
+impl fmt::Display for InvalidDpi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid DPI factor `{}`, expected a positive finite number", self.0)
     }
-    fn poll_event(&mut self) -> Option<Event> {
-        self.inner.poll_event().map(|e| map_input(self.dpi, e))
+}
+
+impl Error for InvalidDpi {}
+
+/// An error returned when DPI factors or a range of DPI factors are invalid.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DpiError {
+    /// A DPI factor or limit is zero, negative or not finite.
+    Invalid(InvalidDpi),
+    /// The minimum DPI factor is greater than the maximum.
+    InvertedRange {
+        /// The minimum DPI factor.
+        min: f64,
+        /// The maximum DPI factor.
+        max: f64,
+    },
+}
+
+impl From<InvalidDpi> for DpiError {
+    fn from(err: InvalidDpi) -> DpiError {DpiError::Invalid(err)}
+}
+
+impl fmt::Display for DpiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DpiError::Invalid(err) => err.fmt(f),
+            DpiError::InvertedRange {min, max} =>
+                write!(f, "Invalid DPI range `{}..={}`, the minimum is greater than the maximum", min, max),
+        }
     }
-    fn draw_size(&self) -> Size {self.inner.draw_size()}
 }
 
-impl<W: AdvancedWindow> AdvancedWindow for FakeDpiWindow<W> {
-    fn get_title(&self) -> String {self.inner.get_title()}
-    fn set_title(&mut self, val: String) {self.inner.set_title(val)}
-    fn get_exit_on_esc(&self) -> bool {self.inner.get_exit_on_esc()}
-    fn set_exit_on_esc(&mut self, val: bool) {self.inner.set_exit_on_esc(val)}
-    fn get_automatic_close(&self) -> bool {self.inner.get_automatic_close()}
-    fn set_automatic_close(&mut self, val: bool) {self.inner.set_automatic_close(val)}
-    fn set_capture_cursor(&mut self, val: bool) {self.inner.set_capture_cursor(val)}
-    fn show(&mut self) {self.inner.show()}
-    fn hide(&mut self) {self.inner.hide()}
-    fn get_position(&self) -> Option<Position> {self.inner.get_position()}
-    fn set_position<P: Into<Position>>(&mut self, val: P) {self.inner.set_position(val)}
-    fn set_size<S: Into<Size>>(&mut self, val: S) {self.inner.set_size(val)}
+impl Error for DpiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DpiError::Invalid(err) => Some(err),
+            DpiError::InvertedRange {..} => None,
+        }
+    }
 }
 
-fn map_input(dpi: f64, e: Event) -> Event {
-    use Input::*;
-    use input::Motion::*;
-    use input::ResizeArgs;
-
-    if let Event::Input(e, ts) = e {
-        Event::Input(match e {
-            Focus(_) | Cursor(_) | Move(Touch(_)) | Move(ControllerAxis(_)) | Button(_) | Text(_) | FileDrag(_) | Close(_) => e,
-            Move(MouseCursor(pos)) => Move(MouseCursor([pos[0] / dpi, pos[1] / dpi])),
-            Move(MouseRelative(pos)) => Move(MouseRelative([pos[0] / dpi, pos[1] / dpi])),
-            Move(MouseScroll(pos)) => Move(MouseScroll([pos[0] / dpi, pos[1] / dpi])),
-            Resize(args) => Resize(ResizeArgs {
-                draw_size: args.draw_size,
-                window_size: [args.window_size[0] / dpi, args.window_size[1] / dpi],
-            })
-        }, ts)
-    } else {
-        e
+/// The simulated orientation of the display, as a clockwise rotation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+    /// Not rotated.
+    #[default]
+    Rotate0,
+    /// Rotated by 90 degrees, which swaps width and height.
+    Rotate90,
+    /// Rotated by 180 degrees.
+    Rotate180,
+    /// Rotated by 270 degrees, which swaps width and height.
+    Rotate270,
+}
+
+impl Orientation {
+    /// Returns whether the orientation swaps width and height.
+    pub fn swaps_axes(self) -> bool {
+        matches!(self, Orientation::Rotate90 | Orientation::Rotate270)
+    }
+
+    /// Returns the orientation that undoes this one.
+    fn inverse(self) -> Orientation {
+        match self {
+            Orientation::Rotate90 => Orientation::Rotate270,
+            Orientation::Rotate270 => Orientation::Rotate90,
+            o => o,
+        }
+    }
+
+    /// Rotates a size.
+    fn size(self, size: Size) -> Size {
+        if self.swaps_axes() {Size {width: size.height, height: size.width}} else {size}
+    }
+
+    /// Rotates a point inside unrotated bounds.
+    ///
+    /// With zero bounds, this rotates a delta.
+    fn point(self, p: [f64; 2], bounds: [f64; 2]) -> [f64; 2] {
+        match self {
+            Orientation::Rotate0 => p,
+            Orientation::Rotate90 => [bounds[1] - p[1], p[0]],
+            Orientation::Rotate180 => [bounds[0] - p[0], bounds[1] - p[1]],
+            Orientation::Rotate270 => [p[1], bounds[0] - p[0]],
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
+/// The parameters of the mapping of mouse cursor positions from physical to logical space.
+///
+/// The steps are applied in the order of the fields.
+/// Without rounding and clamping, the mapping is affine.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CursorTransform {
+    /// The physical resolution that positions are rounded to a multiple of first, if set.
+    pub precision: Option<f64>,
+    /// The physical height that vertical positions are subtracted from, if flipped.
+    pub flip_height: Option<f64>,
+    /// The horizontal and vertical factor that positions are multiplied with.
+    pub scale: [f64; 2],
+    /// The rounding mode applied after scaling.
+    pub rounding: RoundingMode,
+    /// The quantum that positions are rounded to a multiple of after rounding, if set.
+    pub quantum: Option<f64>,
+    /// The rotation applied after quantization.
+    pub orientation: Orientation,
+    /// The unrotated bounds used for the rotation.
+    pub rotation_bounds: [f64; 2],
+    /// The origin subtracted after the rotation.
+    pub origin: [f64; 2],
+    /// The bounds that positions are clamped to at last, if clamping is enabled.
+    pub clamp: Option<[f64; 2]>,
+    /// The distance within which clamped positions snap to the bounds.
+    pub epsilon: f64,
+}
+
+impl CursorTransform {
+    /// Maps a physical mouse cursor position.
+    pub fn apply(&self, p: [f64; 2]) -> [f64; 2] {
+        let p = match self.precision {
+            Some(precision) => [quantize(p[0], precision), quantize(p[1], precision)],
+            None => p,
+        };
+        let p = match self.flip_height {
+            Some(height) => [p[0], height - p[1]],
+            None => p,
+        };
+        let p = [self.rounding.apply(p[0] * self.scale[0]), self.rounding.apply(p[1] * self.scale[1])];
+        let p = match self.quantum {
+            Some(quantum) => [quantize(p[0], quantum), quantize(p[1], quantum)],
+            None => p,
+        };
+        let p = self.orientation.point(p, self.rotation_bounds);
+        let p = [p[0] - self.origin[0], p[1] - self.origin[1]];
+        match self.clamp {
+            Some(bounds) => [
+                clamp_to_edge(p[0], bounds[0], self.epsilon),
+                clamp_to_edge(p[1], bounds[1], self.epsilon),
+            ],
+            None => p,
+        }
+    }
+}
+
+/// The kinds of input events, for describing how they are mapped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InputKind {
+    /// Button events.
+    Button,
+    /// Mouse cursor positions.
+    MouseCursor,
+    /// Relative mouse motion.
+    MouseRelative,
+    /// Mouse scroll deltas.
+    MouseScroll,
+    /// Controller axis positions.
+    ControllerAxis,
+    /// Touch events.
+    Touch,
+    /// Text events.
+    Text,
+    /// Resize events.
+    Resize,
+    /// Focus events.
+    Focus,
+    /// Cursor enter and leave events.
+    Cursor,
+    /// File drag events.
+    FileDrag,
+    /// Close events.
+    Close,
+}
+
+impl InputKind {
+    /// Returns the kind of an input event.
+    pub fn of(e: &Input) -> InputKind {
+        use input::Motion::*;
+
+        match *e {
+            Input::Button(_) => InputKind::Button,
+            Input::Move(MouseCursor(_)) => InputKind::MouseCursor,
+            Input::Move(MouseRelative(_)) => InputKind::MouseRelative,
+            Input::Move(MouseScroll(_)) => InputKind::MouseScroll,
+            Input::Move(ControllerAxis(_)) => InputKind::ControllerAxis,
+            Input::Move(Touch(_)) => InputKind::Touch,
+            Input::Text(_) => InputKind::Text,
+            Input::Resize(_) => InputKind::Resize,
+            Input::Focus(_) => InputKind::Focus,
+            Input::Cursor(_) => InputKind::Cursor,
+            Input::FileDrag(_) => InputKind::FileDrag,
+            Input::Close(_) => InputKind::Close,
+        }
+    }
+}
+
+/// A set of input kinds that may be scaled by the DPI factor.
+///
+/// Masking out a kind passes its events through unchanged, to isolate which code path
+/// a bug lives in. Other options still apply, e.g. mouse scroll is only scaled
+/// with `set_scale_scroll` even if it is in the mask.
+/// Touch positions are normalized and file drag events carry no position,
+/// so their bits have no effect on mapping.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScaleMask(u8);
+
+impl ScaleMask {
+    /// No input kinds.
+    pub const NONE: ScaleMask = ScaleMask(0);
+    /// Mouse cursor positions.
+    pub const CURSOR: ScaleMask = ScaleMask(1);
+    /// Relative mouse motion.
+    pub const RELATIVE: ScaleMask = ScaleMask(1 << 1);
+    /// Mouse scroll deltas.
+    pub const SCROLL: ScaleMask = ScaleMask(1 << 2);
+    /// Touch positions.
+    pub const TOUCH: ScaleMask = ScaleMask(1 << 3);
+    /// The window size of resize events.
+    pub const RESIZE: ScaleMask = ScaleMask(1 << 4);
+    /// File drag events.
+    pub const FILE_DRAG: ScaleMask = ScaleMask(1 << 5);
+    /// All input kinds.
+    pub const ALL: ScaleMask = ScaleMask((1 << 6) - 1);
+
+    /// Returns the bits of the mask.
+    pub fn bits(self) -> u8 {self.0}
+
+    /// Returns whether all kinds of another mask are in this mask.
+    pub fn contains(self, other: ScaleMask) -> bool {self.0 & other.0 == other.0}
+
+    /// Adds the kinds of another mask.
+    pub fn insert(&mut self, other: ScaleMask) {self.0 |= other.0}
+
+    /// Removes the kinds of another mask.
+    pub fn remove(&mut self, other: ScaleMask) {self.0 &= !other.0}
+
+    /// Returns whether events of an input kind may be scaled.
+    ///
+    /// Kinds without a bit are never scaled, so they are allowed.
+    fn allows(self, kind: InputKind) -> bool {
+        let bit = match kind {
+            InputKind::MouseCursor => ScaleMask::CURSOR,
+            InputKind::MouseRelative => ScaleMask::RELATIVE,
+            InputKind::MouseScroll => ScaleMask::SCROLL,
+            InputKind::Touch => ScaleMask::TOUCH,
+            InputKind::Resize => ScaleMask::RESIZE,
+            InputKind::FileDrag => ScaleMask::FILE_DRAG,
+            _ => return true,
+        };
+        self.contains(bit)
+    }
+}
+
+/// Contains mouse cursor positions, relative motion, mouse scroll and resize events.
+impl Default for ScaleMask {
+    fn default() -> ScaleMask {
+        ScaleMask::CURSOR | ScaleMask::RELATIVE | ScaleMask::SCROLL | ScaleMask::RESIZE
+    }
+}
+
+impl BitOr for ScaleMask {
+    type Output = ScaleMask;
+    fn bitor(self, other: ScaleMask) -> ScaleMask {ScaleMask(self.0 | other.0)}
+}
+
+impl BitAnd for ScaleMask {
+    type Output = ScaleMask;
+    fn bitand(self, other: ScaleMask) -> ScaleMask {ScaleMask(self.0 & other.0)}
+}
+
+/// Describes which kinds of input events are scaled by the DPI factor with default options.
+///
+/// Mouse scroll is only scaled with `set_scale_scroll`, and relative mouse motion
+/// is not scaled with `set_scale_relative_motion(false)` or while the cursor is captured.
+/// Touch and controller axis positions are normalized, so they are never scaled.
+pub const INPUT_SCALING: &[(InputKind, bool)] = &[
+    (InputKind::Button, false),
+    (InputKind::MouseCursor, true),
+    (InputKind::MouseRelative, true),
+    (InputKind::MouseScroll, false),
+    (InputKind::ControllerAxis, false),
+    (InputKind::Touch, false),
+    (InputKind::Text, false),
+    (InputKind::Resize, true),
+    (InputKind::Focus, false),
+    (InputKind::Cursor, false),
+    (InputKind::FileDrag, false),
+    (InputKind::Close, false),
+];
+
+/// A simulated monitor with a DPI factor and geometry.
+///
+/// The geometry is in physical desktop pixels, like window positions of the inner window.
+/// Mouse cursor positions from the inner window are relative to the window, so they are
+/// offset by the window position last forwarded by `set_position` before they are compared,
+/// or not at all before the window has been moved.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Monitor {
+    /// The DPI factor.
+    pub dpi: f64,
+    /// The position of the top-left corner.
+    pub position: [f64; 2],
+    /// The width and height.
+    pub size: [f64; 2],
+}
+
+impl Monitor {
+    /// Returns whether the monitor contains a point in physical desktop pixels.
+    ///
+    /// The left and top edges are inside, the right and bottom edges are outside,
+    /// such that adjacent monitors do not overlap.
+    pub fn contains(&self, p: [f64; 2]) -> bool {
+        (0..2).all(|i| p[i] >= self.position[i] && p[i] < self.position[i] + self.size[i])
+    }
+
+    /// Returns whether the monitor contains a point of the virtual desktop.
+    fn contains_logical(&self, p: [f64; 2]) -> bool {
+        let inv_dpi = 1.0 / self.dpi;
+        (0..2).all(|i| p[i] >= self.position[i] && p[i] < self.position[i] + self.size[i] * inv_dpi)
+    }
+
+    /// Maps a point of the virtual desktop to physical space.
+    fn physical_point(&self, p: [f64; 2]) -> [f64; 2] {
+        [
+            self.position[0] + (p[0] - self.position[0]) * self.dpi,
+            self.position[1] + (p[1] - self.position[1]) * self.dpi,
+        ]
+    }
+
+    /// Maps a physical point to the virtual desktop.
+    fn logical_point(&self, p: [f64; 2]) -> [f64; 2] {
+        let inv_dpi = 1.0 / self.dpi;
+        [
+            self.position[0] + (p[0] - self.position[0]) * inv_dpi,
+            self.position[1] + (p[1] - self.position[1]) * inv_dpi,
+        ]
+    }
+}
+
+/// An error returned when a monitor index is out of range.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidMonitor(pub usize);
+
+impl fmt::Display for InvalidMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid monitor index `{}`", self.0)
+    }
+}
+
+impl Error for InvalidMonitor {}
+
+/// A guard that makes polling return only events from the inner window while alive.
+///
+/// Returned by `FakeDpiWindow::suppress_synthetic_events`, and dereferences to the window.
+/// Resize events generated by the wrapper, injected and replayed events are preserved,
+/// and returned in their usual order after the guard is dropped.
+pub struct SuppressSyntheticEvents<'a, W> {
+    window: &'a mut FakeDpiWindow<W>,
+}
+
+impl<'a, W> Deref for SuppressSyntheticEvents<'a, W> {
+    type Target = FakeDpiWindow<W>;
+    fn deref(&self) -> &FakeDpiWindow<W> {self.window}
+}
+
+impl<'a, W> DerefMut for SuppressSyntheticEvents<'a, W> {
+    fn deref_mut(&mut self) -> &mut FakeDpiWindow<W> {self.window}
+}
+
+impl<'a, W> Drop for SuppressSyntheticEvents<'a, W> {
+    fn drop(&mut self) {self.window.suppress_synthetic = false}
+}
+
+/// Clamps a coordinate to `0.0..=max`, snapping values within an epsilon to the edges.
+fn clamp_to_edge(val: f64, max: f64, eps: f64) -> f64 {
+    let val = val.clamp(0.0, max);
+    if val <= eps {0.0} else if max - val <= eps {max} else {val}
+}
+
+/// Rounds a value to the nearest multiple of a quantum.
+fn quantize(val: f64, quantum: f64) -> f64 {(val * (1.0 / quantum)).round() * quantum}
+
+/// Returns the ratio of draw size to size of a window, or one for an empty window.
+fn draw_ratio(size: [f64; 2], draw_size: [f64; 2]) -> [f64; 2] {
+    [0, 1].map(|i| if size[i] > 0.0 && draw_size[i] > 0.0 {draw_size[i] / size[i]} else {1.0})
+}
+
+/// Converts a draw size to whole pixels.
+fn to_pixels(size: Size) -> [u32; 2] {
+    [size.width.round() as u32, size.height.round() as u32]
+}
+
+/// Replaces the DPI suffix of the window title.
+fn retag_title<W: AdvancedWindow>(w: &mut W, dpi: f64) {
+    let title = w.get_title();
+    let base = settings::strip_title_suffix(&title).to_string();
+    w.set_title(base + &settings::title_suffix(dpi));
+}
+
+/// Checks that the DPI factor is positive and finite.
+fn validate_dpi(dpi: f64) -> Result<f64, InvalidDpi> {
+    if dpi.is_finite() && dpi > 0.0 {Ok(dpi)} else {Err(InvalidDpi(dpi))}
+}
+
+/// Returns the horizontal and vertical DPI factor that maps a physical size to a logical size.
+///
+/// This is the physical size divided by the logical size.
+/// A zero logical dimension, e.g. of a minimized window, gives a zero factor instead of
+/// an infinite or `NaN` factor. The DPI setters reject zero factors as invalid.
+pub fn dpi_for(physical: Size, logical: Size) -> [f64; 2] {
+    let factor = |physical: f64, logical: f64| if logical == 0.0 {0.0} else {physical / logical};
+    [factor(physical.width, logical.width), factor(physical.height, logical.height)]
+}
+
+/// Maps an input event from physical to logical space.
+///
+/// This applies the same mapping as a fake DPI window with default options,
+/// for events obtained without a window wrapper:
+/// Mouse cursor positions, relative motion and the window size of resize events
+/// are divided by the DPI factor, while other events are passed through unchanged.
+/// Text events are intentionally left untransformed, because they carry no coordinates.
+///
+/// # Panics
+///
+/// If the DPI factor is zero, negative or not finite.
+pub fn map_input(dpi: f64, e: Input) -> Input {scale_input(&UniformScaling::new(dpi), e)}
+
+/// Maps a generic event from physical to logical space in place.
+///
+/// This applies the same mapping as `map_input` to mouse cursor, relative motion
+/// and resize events, and rebuilds the event with its time stamp kept.
+/// Other events, and events that can not be rebuilt, are left unchanged.
+///
+/// # Panics
+///
+/// If the DPI factor is zero, negative or not finite.
+pub fn map_generic_event<E: GenericEvent>(dpi: f64, e: &mut E) {
+    use input::{MouseCursorEvent, MouseRelativeEvent, ResizeEvent};
+
+    let s = UniformScaling::new(dpi);
+    let mapped = if let Some(pos) = e.mouse_cursor_args() {
+        s.cursor(pos).and_then(|pos| MouseCursorEvent::from_pos(pos, e))
+    } else if let Some(d) = e.mouse_relative_args() {
+        s.relative(d).and_then(|d| MouseRelativeEvent::from_pos(d, e))
+    } else if let Some(args) = e.resize_args() {
+        s.resize(args).and_then(|args| ResizeEvent::from_resize_args(&args, e))
+    } else {
+        None
+    };
+    if let Some(mapped) = mapped {*e = mapped}
+}
+
+impl<W> FakeDpiWindow<W> {
+    /// Wraps an already built window, using the same DPI factor for both axes.
+    ///
+    /// Unlike building from window settings, this does not resize the inner window.
+    /// The caller is responsible for giving the inner window the desired physical size.
+    ///
+    /// The DPI factor is clamped to the default range `0.25..=8.0`.
+    ///
+    /// # Panics
+    ///
+    /// If the DPI factor is zero, negative or not finite.
+    pub fn new(inner: W, dpi: f64) -> FakeDpiWindow<W> {
+        let mut w = FakeDpiWindow {
+            inner,
+            dpi_x: Dpi(DEFAULT_DPI),
+            dpi_y: Dpi(DEFAULT_DPI),
+            inv_dpi: [1.0 / DEFAULT_DPI; 2],
+            input_dpi: None,
+            min_dpi: DEFAULT_MIN_DPI,
+            max_dpi: DEFAULT_MAX_DPI,
+            resize_pending: false,
+            draw_size_offset: None,
+            resize_throttle: Duration::from_secs(0),
+            resize_defer: 0,
+            deferred_polls: 0,
+            resize_debounce: Duration::ZERO,
+            settled_size: None,
+            held_resize: None,
+            input_latency: Duration::from_secs(0),
+            dpi_threshold: None,
+            below_threshold: false,
+            compensate_real_dpi: false,
+            stacked: false,
+            real_dpi: [1.0; 2],
+            delayed: VecDeque::new(),
+            last_resize: None,
+            last_size: None,
+            enabled: true,
+            scale_scroll: false,
+            scale_relative_motion: true,
+            scale_mask: ScaleMask::default(),
+            cursor_captured: false,
+            visible: true,
+            check_size_ratio: false,
+            rounding: RoundingMode::None,
+            cursor_space: CoordinateSpace::Logical,
+            clamp_cursor: false,
+            epsilon: DEFAULT_EPSILON,
+            flip_y: false,
+            orientation: Orientation::Rotate0,
+            report_physical_size: false,
+            strict: false,
+            size_check_tolerance: None,
+            origin: [0.0; 2],
+            letterbox: None,
+            paired_scroll: None,
+            scroll_step: None,
+            derive_relative_motion: false,
+            event_stats: None,
+            dpi_hotkey: None,
+            dpi_hotkey_step: 0.25,
+            swallow_dpi_hotkey: false,
+            auto_close_after: None,
+            frames: 0,
+            hooks: Hooks::default(),
+            retag_title: None,
+            monitors: vec![],
+            active_monitor: None,
+            monitor_layout: vec![],
+            virtual_desktop: false,
+            window_position: None,
+            dpi_presets: DEFAULT_DPI_PRESETS.to_vec(),
+            last_preset: None,
+            dpi_steps: vec![],
+            injected: VecDeque::new(),
+            paused: false,
+            buffered: VecDeque::new(),
+            paired: None,
+            scroll_accum: [0.0; 2],
+            prev_cursor: None,
+            last_event: None,
+            last_cursor: None,
+            last_resize_mapping: None,
+            last_resize_synthetic: None,
+            cursor_quantum: None,
+            input_precision: None,
+            synthetic: false,
+            suppress_synthetic: false,
+            peeked: None,
+            #[cfg(feature = "recording")]
+            replay: VecDeque::new(),
+            animation: None,
+            #[cfg(feature = "random")]
+            dpi_rng: std::cell::RefCell::new(rand::SeedableRng::seed_from_u64(0)),
+            #[cfg(feature = "random")]
+            dpi_jitter: 0.0,
+        };
+        if let Err(err) = w.set_dpi(dpi) {panic!("{}", err)}
+        w.resize_pending = false;
+        w
+    }
+
+    /// Wraps an already built window, using a configuration.
+    ///
+    /// Like `new`, this does not resize the inner window.
+    /// Returns an error if any DPI factor or limit is zero, negative or not finite,
+    /// or if the minimum DPI factor is greater than the maximum.
+    /// The DPI factors are clamped to the configured range.
+    pub fn from_config(inner: W, config: FakeDpiConfig) -> Result<FakeDpiWindow<W>, DpiError> {
+        let mut w = FakeDpiWindow::new(inner, DEFAULT_DPI);
+        w.set_dpi_range(config.min_dpi, config.max_dpi)?;
+        w.set_dpi_xy(config.dpi_x, config.dpi_y)?;
+        w.rounding = config.rounding;
+        w.scale_scroll = config.scale_scroll;
+        w.resize_pending = false;
+        Ok(w)
+    }
+
+    /// Wraps an already built window, using a configuration file.
+    ///
+    /// The format is inferred from the extension, see `FakeDpiConfig::from_file`.
+    /// Like `from_config`, this does not resize the inner window,
+    /// and the DPI factors are validated and clamped to the configured range.
+    /// Returns an error if the file can not be read or parsed, or if the configuration is invalid.
+    #[cfg(feature = "serde")]
+    pub fn from_config_file<P: AsRef<std::path::Path>>(
+        inner: W,
+        path: P
+    ) -> Result<FakeDpiWindow<W>, ConfigFileError> {
+        let config = FakeDpiConfig::from_file(path)?;
+        FakeDpiWindow::from_config(inner, config).map_err(ConfigFileError::Invalid)
+    }
+
+    /// Wraps an already built window, applying a complete configuration at once.
+    ///
+    /// This is like `from_config`, but panics on an invalid configuration like `new`,
+    /// for configurations that are known to be valid.
+    /// It does not resize the inner window.
+    ///
+    /// # Panics
+    ///
+    /// If any DPI factor or limit is zero, negative or not finite,
+    /// or if the minimum DPI factor is greater than the maximum.
+    pub fn with_config(inner: W, config: FakeDpiConfig) -> FakeDpiWindow<W> {
+        match FakeDpiWindow::from_config(inner, config) {
+            Ok(w) => w,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Creates a builder for wrapping an already built window.
+    pub fn builder(inner: W) -> FakeDpiWindowBuilder<W> {FakeDpiWindowBuilder::new(inner)}
+
+    /// Resets the configuration to the defaults of `new` with DPI factor `2.0`.
+    ///
+    /// This resets the DPI factors and range, the input DPI factor, all flags, the last event,
+    /// the rounding mode, the cursor space, the resize throttle, the monitors, the DPI presets,
+    /// and removes all callbacks, loggers, transforms and hooks.
+    /// Updating the title suffix stops, but the current title is kept.
+    /// Running DPI animations are stopped, and pending synthetic, injected and
+    /// replayed events are discarded.
+    /// The inner window, its visibility and whether the cursor is captured are not changed.
+    /// If the reset changes the logical size, a resize event is emitted.
+    pub fn reset_config(&mut self) {
+        let FakeDpiWindow {
+            inner: (),
+            dpi_x,
+            dpi_y,
+            inv_dpi,
+            input_dpi,
+            min_dpi,
+            max_dpi,
+            resize_pending: _,
+            draw_size_offset,
+            resize_throttle,
+            resize_defer,
+            deferred_polls: _,
+            resize_debounce,
+            settled_size,
+            held_resize,
+            input_latency,
+            dpi_threshold,
+            below_threshold,
+            compensate_real_dpi,
+            stacked: _,
+            real_dpi,
+            delayed: _,
+            last_resize,
+            last_size,
+            enabled,
+            scale_scroll,
+            scale_relative_motion,
+            scale_mask,
+            cursor_captured: _,
+            visible: _,
+            check_size_ratio,
+            rounding,
+            cursor_space,
+            clamp_cursor,
+            epsilon,
+            flip_y,
+            orientation,
+            report_physical_size,
+            strict,
+            size_check_tolerance,
+            origin,
+            letterbox,
+            paired_scroll,
+            scroll_step,
+            derive_relative_motion,
+            event_stats,
+            dpi_hotkey,
+            dpi_hotkey_step,
+            swallow_dpi_hotkey,
+            auto_close_after,
+            frames,
+            hooks,
+            retag_title: _,
+            monitors,
+            active_monitor,
+            monitor_layout,
+            virtual_desktop,
+            window_position: _,
+            dpi_presets,
+            last_preset,
+            dpi_steps,
+            injected,
+            paused,
+            buffered: _,
+            paired,
+            scroll_accum,
+            prev_cursor,
+            last_event,
+            last_cursor,
+            last_resize_mapping,
+            last_resize_synthetic,
+            cursor_quantum,
+            input_precision,
+            synthetic: _,
+            suppress_synthetic: _,
+            peeked: _,
+            #[cfg(feature = "recording")]
+            replay,
+            animation,
+            #[cfg(feature = "random")]
+            dpi_rng,
+            #[cfg(feature = "random")]
+            dpi_jitter,
+        } = FakeDpiWindow::new((), DEFAULT_DPI);
+        let changed = [dpi_x, dpi_y] != [self.dpi_x, self.dpi_y] || enabled != self.enabled ||
+                      rounding != self.rounding || orientation.swaps_axes() != self.orientation.swaps_axes() ||
+                      report_physical_size != self.report_physical_size ||
+                      below_threshold != self.below_threshold ||
+                      compensate_real_dpi != self.compensate_real_dpi || self.settled_size.is_some();
+        self.dpi_x = dpi_x;
+        self.dpi_y = dpi_y;
+        self.inv_dpi = inv_dpi;
+        self.input_dpi = input_dpi;
+        self.min_dpi = min_dpi;
+        self.max_dpi = max_dpi;
+        self.resize_pending = changed;
+        self.draw_size_offset = draw_size_offset;
+        self.resize_throttle = resize_throttle;
+        self.resize_defer = resize_defer;
+        self.resize_debounce = resize_debounce;
+        self.settled_size = settled_size;
+        self.held_resize = held_resize;
+        self.input_latency = input_latency;
+        self.dpi_threshold = dpi_threshold;
+        self.below_threshold = below_threshold;
+        self.compensate_real_dpi = compensate_real_dpi;
+        self.real_dpi = real_dpi;
+        self.last_resize = last_resize;
+        self.last_size = last_size;
+        self.enabled = enabled;
+        self.scale_scroll = scale_scroll;
+        self.scale_relative_motion = scale_relative_motion;
+        self.scale_mask = scale_mask;
+        self.check_size_ratio = check_size_ratio;
+        self.rounding = rounding;
+        self.cursor_space = cursor_space;
+        self.clamp_cursor = clamp_cursor;
+        self.epsilon = epsilon;
+        self.flip_y = flip_y;
+        self.orientation = orientation;
+        self.report_physical_size = report_physical_size;
+        self.strict = strict;
+        self.size_check_tolerance = size_check_tolerance;
+        self.origin = origin;
+        self.letterbox = letterbox;
+        self.paired_scroll = paired_scroll;
+        self.scroll_step = scroll_step;
+        self.derive_relative_motion = derive_relative_motion;
+        self.event_stats = event_stats;
+        self.dpi_hotkey = dpi_hotkey;
+        self.dpi_hotkey_step = dpi_hotkey_step;
+        self.swallow_dpi_hotkey = swallow_dpi_hotkey;
+        self.auto_close_after = auto_close_after;
+        self.frames = frames;
+        self.hooks = hooks;
+        self.retag_title = None;
+        self.monitors = monitors;
+        self.active_monitor = active_monitor;
+        self.monitor_layout = monitor_layout;
+        self.virtual_desktop = virtual_desktop;
+        self.dpi_presets = dpi_presets;
+        self.last_preset = last_preset;
+        self.dpi_steps = dpi_steps;
+        self.injected = injected;
+        self.paired = paired;
+        self.scroll_accum = scroll_accum;
+        self.prev_cursor = prev_cursor;
+        self.paused = paused;
+        self.last_event = last_event;
+        self.last_cursor = last_cursor;
+        self.last_resize_mapping = last_resize_mapping;
+        self.last_resize_synthetic = last_resize_synthetic;
+        self.cursor_quantum = cursor_quantum;
+        self.input_precision = input_precision;
+        #[cfg(feature = "recording")]
+        {
+            self.replay = replay;
+        }
+        self.animation = animation;
+        #[cfg(feature = "random")]
+        {
+            self.dpi_rng = dpi_rng;
+            self.dpi_jitter = dpi_jitter;
+        }
+    }
+
+    /// Unwraps the inner window.
+    ///
+    /// The inner window keeps its physical size, which is the logical size
+    /// multiplied by the DPI factor when built from window settings.
+    pub fn into_inner(self) -> W {self.inner}
+
+    /// Returns the horizontal DPI factor.
+    ///
+    /// This is the same as the vertical factor unless they were set separately.
+    /// By default, this is set to `2.0`.
+    /// With a DPI jitter, each call returns a perturbed factor.
+    pub fn dpi(&self) -> f64 {self.read_dpi()[0]}
+
+    /// Returns the horizontal DPI factor.
+    pub fn dpi_x(&self) -> f64 {self.read_dpi()[0]}
+
+    /// Returns the vertical DPI factor.
+    pub fn dpi_y(&self) -> f64 {self.read_dpi()[1]}
+
+    /// Returns the DPI factors as read for mapping, with a new random jitter if enabled.
+    fn read_dpi(&self) -> [f64; 2] {
+        #[cfg(feature = "random")]
+        {
+            use rand::RngExt;
+
+            if self.has_jitter() {
+                let amplitude = self.dpi_jitter();
+                let offset = self.dpi_rng.borrow_mut().random_range(-amplitude..=amplitude);
+                return self.dpi_xy().map(|dpi| (dpi + offset).clamp(self.min_dpi, self.max_dpi))
+            }
+        }
+        self.dpi_xy()
+    }
+
+    /// Returns whether the DPI factor is jittered on each read.
+    #[cfg(feature = "random")]
+    fn has_jitter(&self) -> bool {self.dpi_jitter() > 0.0}
+
+    /// Returns whether the DPI factor is jittered on each read.
+    #[cfg(not(feature = "random"))]
+    fn has_jitter(&self) -> bool {false}
+
+    /// Returns the amplitude of the random DPI jitter.
+    #[cfg(feature = "random")]
+    pub fn dpi_jitter(&self) -> f64 {
+        if self.dpi_jitter.is_finite() {self.dpi_jitter.max(0.0)} else {0.0}
+    }
+
+    /// Sets the amplitude of a random jitter that is added to the DPI factor on each read.
+    ///
+    /// This simulates drivers that report slightly different factors on each query,
+    /// and reveals code that caches the factor instead of reading it again.
+    /// Each call of `dpi`, `dpi_x`, `dpi_y`, `scale_factor` and each mapping of a coordinate
+    /// draws an offset within `-amplitude..=amplitude` and adds it to both factors,
+    /// then clamps them to the DPI range. The stored factor does not change,
+    /// and synthetic resize events are only emitted by `set_dpi` as usual.
+    /// The offsets are drawn from the generator seeded with `set_dpi_seed`.
+    /// An amplitude that is not positive and finite disables the jitter. By default, this is `0.0`.
+    #[cfg(feature = "random")]
+    pub fn set_dpi_jitter(&mut self, amplitude: f64) {self.dpi_jitter = amplitude}
+
+    /// Returns the horizontal scale factor, like `scale_factor` in other windowing libraries.
+    ///
+    /// This is the factor used for all coordinate mapping,
+    /// which is `1.0` while the scaling is disabled.
+    pub fn scale_factor(&self) -> f64 {self.scale()[0]}
+
+    /// Returns the horizontal and vertical scale factors used for all coordinate mapping.
+    pub fn scale_factors(&self) -> [f64; 2] {self.scale()}
+
+    /// Returns the minimum and maximum DPI factor.
+    pub fn dpi_range(&self) -> [f64; 2] {[self.min_dpi, self.max_dpi]}
+
+    /// Sets the minimum and maximum DPI factor.
+    ///
+    /// The current DPI factors are clamped to the new range,
+    /// which emits a resize event if they change.
+    /// Returns an error if any limit is zero, negative or not finite,
+    /// or if `min` is greater than `max`, without changing the range.
+    pub fn set_dpi_range(&mut self, min: f64, max: f64) -> Result<(), DpiError> {
+        let min = validate_dpi(min)?;
+        let max = validate_dpi(max)?;
+        if min > max {return Err(DpiError::InvertedRange {min, max})}
+        self.min_dpi = min;
+        self.max_dpi = max;
+        let dpi = [self.dpi_x.clamp(min, max), self.dpi_y.clamp(min, max)];
+        if dpi != self.dpi_xy() {
+            self.apply_dpi(dpi);
+        }
+        Ok(())
+    }
+
+    /// Sets both horizontal and vertical DPI factor.
+    ///
+    /// This can be changed at run-time to test application logic.
+    /// Returns an error if the DPI factor is zero, negative or not finite.
+    /// Valid factors are then clamped to the DPI range.
+    ///
+    /// The next polled event is a resize event with the new logical window size,
+    /// like when a real Hi-DPI screen changes scale.
+    /// Several changes before polling produce a single resize event.
+    /// Setting the current factor again, after clamping and snapping, does nothing.
+    /// Accepts a `Dpi` as well as a plain number.
+    pub fn set_dpi<D: Into<f64>>(&mut self, dpi: D) -> Result<(), InvalidDpi> {
+        let dpi = dpi.into();
+        self.set_dpi_xy(dpi, dpi)
+    }
+
+    /// Returns the horizontal DPI factor as a percentage, e.g. `150` for `1.5`.
+    ///
+    /// Factors that are not whole percentages are rounded to the nearest percentage.
+    pub fn dpi_percent(&self) -> u32 {(self.dpi() * 100.0).round() as u32}
+
+    /// Sets both horizontal and vertical DPI factor as a percentage, e.g. `150` for `1.5`.
+    ///
+    /// This behaves like `set_dpi` with the percentage divided by 100,
+    /// including clamping and snapping to the allowed steps.
+    /// Returns an error if the percentage is zero.
+    pub fn set_dpi_percent(&mut self, percent: u32) -> Result<(), InvalidDpi> {
+        self.set_dpi(percent as f64 / 100.0)
+    }
+
+    /// Sets a random DPI factor between `min` and `max`, inclusive.
+    ///
+    /// This behaves like `set_dpi` with the chosen factor,
+    /// including clamping, snapping and the resize event.
+    /// The factors are drawn from a generator seeded with `set_dpi_seed`,
+    /// so the same seed gives the same sequence on every run.
+    /// The bounds may be given in any order.
+    /// Returns an error if any bound is zero, negative or not finite.
+    #[cfg(feature = "random")]
+    pub fn randomize_dpi(&mut self, min: f64, max: f64) -> Result<(), InvalidDpi> {
+        use rand::RngExt;
+
+        let min = validate_dpi(min)?;
+        let max = validate_dpi(max)?;
+        let dpi = self.dpi_rng.get_mut().random_range(min.min(max)..=min.max(max));
+        self.set_dpi(dpi)
+    }
+
+    /// Seeds the generator used by `randomize_dpi` and the DPI jitter.
+    ///
+    /// By default, the seed is `0`.
+    #[cfg(feature = "random")]
+    pub fn set_dpi_seed(&mut self, seed: u64) {
+        self.dpi_rng = std::cell::RefCell::new(rand::SeedableRng::seed_from_u64(seed));
+    }
+
+    /// Sets horizontal and vertical DPI factor separately.
+    ///
+    /// Returns an error if any DPI factor is zero, negative or not finite.
+    /// In that case, neither factor is changed.
+    /// Valid factors are then clamped to the DPI range.
+    /// This stops any running DPI animation.
+    /// Accepts a `Dpi` as well as a plain number.
+    pub fn set_dpi_xy<D: Into<f64>>(&mut self, dpi_x: D, dpi_y: D) -> Result<(), InvalidDpi> {
+        let dpi_x = self.normalize_dpi(dpi_x.into())?;
+        let dpi_y = self.normalize_dpi(dpi_y.into())?;
+        self.animation = None;
+        self.apply_dpi([dpi_x, dpi_y]);
+        Ok(())
+    }
+
+    /// Returns the DPI factor used for size reporting.
+    ///
+    /// This is the same as `dpi`.
+    pub fn size_dpi(&self) -> f64 {self.dpi_x.get()}
+
+    /// Returns the DPI factor used for scaling pointer input.
+    ///
+    /// This is the same as `dpi` unless set separately.
+    pub fn input_dpi(&self) -> f64 {self.input_dpi.unwrap_or(self.dpi_x.get())}
+
+    /// Sets a separate DPI factor for scaling pointer input, or `None` to use the DPI factor.
+    ///
+    /// This decouples the factor used for mouse cursor positions, relative motion and scroll
+    /// from the factor used for `size()` and resize events,
+    /// to test applications that assume both are always equal.
+    /// Note that this simulates an inconsistent platform state,
+    /// in which pointer positions do not line up with the reported window size.
+    /// The factor applies to both axes.
+    /// Returns an error if the DPI factor is zero, negative or not finite.
+    /// Valid factors are then clamped to the DPI range.
+    pub fn set_input_dpi(&mut self, dpi: Option<f64>) -> Result<(), InvalidDpi> {
+        self.input_dpi = match dpi {
+            Some(dpi) => Some(self.normalize_dpi(dpi)?),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Converts pointer input from physical to logical space.
+    fn input_to_logical(&self, p: [f64; 2]) -> [f64; 2] {
+        match self.input_dpi {
+            Some(dpi) if self.scaling() => {
+                let real = self.real_scale();
+                [p[0] * (1.0 / dpi) * real[0], p[1] * (1.0 / dpi) * real[1]]
+            }
+            _ => self.physical_to_logical(p),
+        }
+    }
+
+    /// Converts pointer input from logical to physical space.
+    fn input_to_physical(&self, p: [f64; 2]) -> [f64; 2] {
+        match self.input_dpi {
+            Some(dpi) if self.scaling() => {
+                let real = self.real_scale();
+                [p[0] * dpi / real[0], p[1] * dpi / real[1]]
+            }
+            _ => self.logical_to_physical(p),
+        }
+    }
+
+    /// Runs a closure at a different DPI factor, then restores the previous DPI factors.
+    ///
+    /// The DPI factors are restored even if the closure panics.
+    /// Both changes emit resize events like `set_dpi`.
+    /// Returns an error if the DPI factor is zero, negative or not finite,
+    /// in which case the closure is not called.
+    pub fn with_dpi<R, F>(&mut self, dpi: f64, f: F) -> Result<R, InvalidDpi>
+        where F: FnOnce(&mut Self) -> R
+    {
+        self.with_dpi_impl(dpi, false, f)
+    }
+
+    /// Runs a closure at a different DPI factor without resize events,
+    /// then restores the previous DPI factors.
+    ///
+    /// This behaves like `with_dpi`, but the pending resize state is kept as before,
+    /// such that neither the change nor the restore emits a resize event.
+    pub fn with_dpi_quiet<R, F>(&mut self, dpi: f64, f: F) -> Result<R, InvalidDpi>
+        where F: FnOnce(&mut Self) -> R
+    {
+        self.with_dpi_impl(dpi, true, f)
+    }
+
+    /// Runs a closure once per DPI factor and collects the results.
+    ///
+    /// Each run behaves like `with_dpi`, so the previous DPI factors are restored
+    /// after each run, and even if the closure panics.
+    /// The closure receives the DPI factor after clamping to the DPI range.
+    /// Returns an error if any DPI factor is zero, negative or not finite,
+    /// in which case the closure is not called at all.
+    pub fn for_each_dpi<R, F>(&mut self, dpis: &[f64], mut f: F) -> Result<Vec<R>, InvalidDpi>
+        where F: FnMut(&mut Self, f64) -> R
+    {
+        for &dpi in dpis {validate_dpi(dpi)?;}
+        dpis.iter().map(|&dpi| self.with_dpi(dpi, |w| {
+            let dpi = w.dpi();
+            f(w, dpi)
+        })).collect()
+    }
+
+    /// Runs a closure at a different DPI factor, optionally keeping the pending resize state.
+    fn with_dpi_impl<R, F>(&mut self, dpi: f64, quiet: bool, f: F) -> Result<R, InvalidDpi>
+        where F: FnOnce(&mut Self) -> R
+    {
+        /// Restores the DPI factors when dropped.
+        struct Guard<'a, W> {
+            window: &'a mut FakeDpiWindow<W>,
+            dpi: [f64; 2],
+            resize_pending: Option<bool>,
+        }
+
+        impl<'a, W> Drop for Guard<'a, W> {
+            fn drop(&mut self) {
+                self.window.apply_dpi(self.dpi);
+                if let Some(val) = self.resize_pending {self.window.resize_pending = val}
+            }
+        }
+
+        let prev = self.dpi_xy();
+        let resize_pending = self.resize_pending;
+        self.set_dpi(dpi)?;
+        if quiet {self.resize_pending = resize_pending}
+        let guard = Guard {window: self, dpi: prev, resize_pending: if quiet {Some(resize_pending)} else {None}};
+        Ok(f(&mut *guard.window))
+    }
+
+    /// Returns the DPI factors of simulated monitors.
+    pub fn monitors(&self) -> &[f64] {&self.monitors}
+
+    /// Sets the DPI factors of simulated monitors.
+    ///
+    /// This simulates moving the window between screens with different DPI,
+    /// by switching the active monitor with `set_active_monitor`.
+    /// Returns an error if any DPI factor is zero, negative or not finite.
+    /// Clears the active monitor and the monitor layout without changing the current DPI factor.
+    pub fn set_monitors(&mut self, monitors: Vec<f64>) -> Result<(), InvalidDpi> {
+        for &dpi in &monitors {validate_dpi(dpi)?;}
+        self.monitors = monitors;
+        self.monitor_layout.clear();
+        self.active_monitor = None;
+        Ok(())
+    }
+
+    /// Returns the simulated monitors with geometry.
+    pub fn monitor_layout(&self) -> &[Monitor] {&self.monitor_layout}
+
+    /// Sets simulated monitors with geometry.
+    ///
+    /// This simulates a setup with mixed DPI monitors, where the DPI factor
+    /// depends on the monitor under the cursor. Mouse cursor positions from the inner window
+    /// switch the active monitor with `set_active_monitor` when they move onto another monitor,
+    /// before the position is mapped with the new DPI factor.
+    /// The geometry is physical, because logical rectangles at different DPI factors
+    /// would not line up in physical space, and the active monitor would flip at edges.
+    /// See `Monitor` for how cursor positions are placed on the desktop.
+    /// Also sets the DPI factors returned by `monitors`.
+    /// Returns an error if any DPI factor is zero, negative or not finite.
+    /// Clears the active monitor without changing the current DPI factor.
+    pub fn set_monitor_layout(&mut self, layout: Vec<Monitor>) -> Result<(), InvalidDpi> {
+        self.set_monitors(layout.iter().map(|m| m.dpi).collect())?;
+        self.monitor_layout = layout;
+        Ok(())
+    }
+
+    /// Returns whether window positions are mapped into a virtual desktop.
+    pub fn get_virtual_desktop(&self) -> bool {self.virtual_desktop}
+
+    /// Sets whether window positions are mapped into a virtual desktop.
+    ///
+    /// The virtual desktop is a logical coordinate space laid out by the monitor layout.
+    /// Each monitor keeps its top-left corner, and covers its physical size divided by its DPI factor,
+    /// such that a monitor at `[1920, 0]` with size `2560x1440` at `2.0` covers `[1920, 0]..[3200, 720]`.
+    /// Monitors at different DPI factors may leave gaps between them in the virtual desktop,
+    /// like in per-monitor DPI setups that keep the corners of monitors fixed.
+    ///
+    /// With this enabled, `set_position` maps a logical position on the first monitor that contains it
+    /// to the physical position forwarded to the inner window, and switches the active monitor
+    /// with `set_active_monitor` when the window moves onto another monitor.
+    /// `get_position` maps the physical position back on the first monitor that contains it.
+    /// Positions outside all monitors, or without a monitor layout, are passed through unchanged.
+    /// While the inner window stays where it was moved, `get_position` returns the position
+    /// passed to `set_position`, which also holds for positions in gaps and for rounded positions.
+    /// By default, this is off.
+    pub fn set_virtual_desktop(&mut self, val: bool) {self.virtual_desktop = val}
+
+    /// Maps a physical window position to the virtual desktop, if enabled.
+    fn desktop_logical(&self, pos: Position) -> Position {
+        let p = [pos.x as f64, pos.y as f64];
+        if !self.virtual_desktop {return pos}
+        if let Some((requested, forwarded)) = self.window_position {
+            if forwarded == pos {return requested}
+        }
+        match self.monitor_at(p) {
+            Some(idx) => {
+                let p = self.monitor_layout[idx].logical_point(p);
+                Position {x: p[0].round() as i32, y: p[1].round() as i32}
+            }
+            None => pos,
+        }
+    }
+
+    /// Returns the index of the first monitor in the layout that contains a point
+    /// in physical desktop pixels.
+    pub fn monitor_at(&self, p: [f64; 2]) -> Option<usize> {
+        self.monitor_layout.iter().position(|m| m.contains(p))
+    }
+
+    /// Returns the index of the active monitor, if any.
+    pub fn active_monitor(&self) -> Option<usize> {self.active_monitor}
+
+    /// Moves the window to a simulated monitor.
+    ///
+    /// The DPI factor is set to the monitor's factor, which emits a resize event.
+    /// Returns an error if the index is out of range.
+    pub fn set_active_monitor(&mut self, idx: usize) -> Result<(), InvalidMonitor> {
+        let dpi = *self.monitors.get(idx).ok_or(InvalidMonitor(idx))?;
+        self.set_dpi(dpi).expect("monitor DPI factors are validated");
+        self.active_monitor = Some(idx);
+        Ok(())
+    }
+
+    /// Returns the key that changes the DPI factor when pressed.
+    pub fn dpi_hotkey(&self) -> Option<Key> {self.dpi_hotkey}
+
+    /// Sets a key that changes the DPI factor when pressed, or `None` to disable it.
+    ///
+    /// Each press from the inner window adds the hotkey step to the DPI factor with `set_dpi`,
+    /// which emits a resize event.
+    /// The key events are passed through to the application,
+    /// unless swallowing them is enabled with `set_swallow_dpi_hotkey`.
+    /// By default, there is no hotkey.
+    pub fn set_dpi_hotkey(&mut self, key: Option<Key>) {self.dpi_hotkey = key}
+
+    /// Returns the step added to the DPI factor when pressing the hotkey.
+    pub fn dpi_hotkey_step(&self) -> f64 {self.dpi_hotkey_step}
+
+    /// Sets the step added to the DPI factor when pressing the hotkey.
+    ///
+    /// Use a negative step to decrease the DPI factor.
+    /// The result is clamped to the DPI range.
+    /// By default, this is `0.25`.
+    pub fn set_dpi_hotkey_step(&mut self, val: f64) {self.dpi_hotkey_step = val}
+
+    /// Returns whether key events of the DPI hotkey are swallowed.
+    pub fn get_swallow_dpi_hotkey(&self) -> bool {self.swallow_dpi_hotkey}
+
+    /// Sets whether key events of the DPI hotkey are swallowed.
+    ///
+    /// When enabled, presses and releases of the hotkey are not returned to the application.
+    /// By default, this is disabled.
+    pub fn set_swallow_dpi_hotkey(&mut self, val: bool) {self.swallow_dpi_hotkey = val}
+
+    /// Returns the DPI presets.
+    pub fn dpi_presets(&self) -> &[f64] {&self.dpi_presets}
+
+    /// Sets the DPI presets used by `cycle_dpi_preset`.
+    ///
+    /// By default, the presets are `1.0`, `1.25`, `1.5`, `2.0` and `3.0`.
+    /// Returns an error if any DPI factor is zero, negative or not finite.
+    pub fn set_dpi_presets(&mut self, presets: Vec<f64>) -> Result<(), InvalidDpi> {
+        for &dpi in &presets {validate_dpi(dpi)?;}
+        self.dpi_presets = presets;
+        self.last_preset = None;
+        Ok(())
+    }
+
+    /// Returns the allowed DPI steps, or an empty list if any DPI factor is allowed.
+    pub fn allowed_dpi_steps(&self) -> &[f64] {&self.dpi_steps}
+
+    /// Sets the allowed DPI steps, to emulate platforms with discrete scale factors.
+    ///
+    /// When set, DPI factors snap to the nearest step before they are clamped to the DPI range,
+    /// e.g. `1.6` becomes `1.5` with steps `1.5` and `1.75`.
+    /// This does not change the current DPI factor.
+    /// Returns an error if any DPI factor is zero, negative or not finite.
+    pub fn set_allowed_dpi_steps(&mut self, steps: Vec<f64>) -> Result<(), InvalidDpi> {
+        for &dpi in &steps {validate_dpi(dpi)?;}
+        self.dpi_steps = steps;
+        Ok(())
+    }
+
+    /// Clears the allowed DPI steps, such that any DPI factor is allowed.
+    pub fn clear_allowed_dpi_steps(&mut self) {self.dpi_steps.clear()}
+
+    /// Sets the DPI factor to the next preset, wrapping around.
+    ///
+    /// The next preset follows the preset applied last, as long as the DPI factor
+    /// was not changed since, even if the preset was clamped to the DPI range or snapped.
+    /// Otherwise, when the current DPI factor is not a preset,
+    /// this picks the first preset greater than the current factor.
+    /// The change goes through `set_dpi`, so it emits a resize event.
+    /// Does nothing if there are no presets.
+    pub fn cycle_dpi_preset(&mut self) {
+        if self.dpi_presets.is_empty() {return}
+        let dpi = self.dpi_x.get();
+        let last = self.last_preset.filter(|&(_, applied)| applied == dpi).map(|(i, _)| i);
+        let next = match last.or_else(|| self.dpi_presets.iter().position(|&p| p == dpi)) {
+            Some(i) => (i + 1) % self.dpi_presets.len(),
+            None => self.dpi_presets.iter().position(|&p| p > dpi).unwrap_or(0),
+        };
+        let preset = self.dpi_presets[next];
+        self.set_dpi(preset).expect("DPI presets are validated");
+        self.last_preset = Some((next, self.dpi_x.get()));
+    }
+
+    /// Changes the DPI factor smoothly to a target value over some duration.
+    ///
+    /// Uses linear interpolation. See `animate_dpi_with` for custom easing.
+    pub fn animate_dpi(&mut self, target: f64, duration: Duration) -> Result<(), InvalidDpi> {
+        self.animate_dpi_with(target, duration, ease_linear)
+    }
+
+    /// Changes the DPI factor smoothly to a target value using an easing function.
+    ///
+    /// The easing function maps elapsed time in `0.0..=1.0` to progress,
+    /// where `0.0` is the current DPI factor and `1.0` is the target.
+    /// The DPI factor is updated each time events are polled,
+    /// emitting resize events as the value changes.
+    /// When the animation completes, the DPI factor is exactly the target.
+    ///
+    /// Returns an error if the target is zero, negative or not finite.
+    pub fn animate_dpi_with(
+        &mut self,
+        target: f64,
+        duration: Duration,
+        easing: fn(f64) -> f64
+    ) -> Result<(), InvalidDpi> {
+        let target = self.normalize_dpi(target)?;
+        self.animation = Some(DpiAnimation {
+            from: self.dpi_xy(),
+            to: target,
+            start: Instant::now(),
+            duration,
+            easing,
+        });
+        self.advance_animation(Instant::now());
+        Ok(())
+    }
+
+    /// Returns `true` while a DPI animation is running.
+    pub fn is_animating_dpi(&self) -> bool {self.animation.is_some()}
+
+    /// Updates the DPI factor of a running animation.
+    fn advance_animation(&mut self, now: Instant) {
+        let (dpi, done) = match self.animation {
+            None => return,
+            Some(ref anim) => {
+                let elapsed = now.saturating_duration_since(anim.start);
+                if elapsed >= anim.duration {
+                    ([anim.to; 2], true)
+                } else {
+                    let t = (anim.easing)(elapsed.as_secs_f64() / anim.duration.as_secs_f64());
+                    let lerp = |from: f64| from + (anim.to - from) * t;
+                    ([lerp(anim.from[0]), lerp(anim.from[1])], false)
+                }
+            }
+        };
+        if done {self.animation = None}
+        let dpi = [
+            dpi[0].clamp(self.min_dpi, self.max_dpi),
+            dpi[1].clamp(self.min_dpi, self.max_dpi),
+        ];
+        if dpi != self.dpi_xy() {self.apply_dpi(dpi)}
+    }
+
+    /// Returns the minimum interval between resize events generated by the wrapper.
+    pub fn get_resize_throttle(&self) -> Duration {self.resize_throttle}
+
+    /// Sets the minimum interval between resize events generated by the wrapper.
+    ///
+    /// DPI changes within the interval are coalesced into a single resize event
+    /// with the latest DPI factor, which is emitted once the interval has passed.
+    /// Waiting for events wakes up in time to emit it.
+    /// By default, there is no throttling.
+    pub fn set_resize_throttle(&mut self, val: Duration) {self.resize_throttle = val}
+
+    /// Returns the number of polls that resize events after DPI changes are deferred by.
+    pub fn get_resize_defer(&self) -> u32 {self.resize_defer}
+
+    /// Sets the number of polls that resize events after DPI changes are deferred by.
+    ///
+    /// Some platforms deliver the resize notification a frame or two after the scale changed.
+    /// With a defer, `size()` reflects the new DPI factor right away, while the resize event
+    /// is only emitted after this many polls, which return other events or `None`.
+    /// Waiting counts each check of the inner window as a poll, without blocking,
+    /// so waiting on an idle window still returns the deferred resize event.
+    /// A DPI change while a resize event is deferred starts the defer again.
+    /// Other resize events generated by the wrapper are not deferred.
+    /// By default, this is `0`.
+    pub fn set_resize_defer(&mut self, polls: u32) {self.resize_defer = polls}
+
+    /// Returns the quiet interval after which debounced resize events are emitted.
+    pub fn get_resize_debounce(&self) -> Duration {self.resize_debounce}
+
+    /// Holds back a resize event of the inner window while debouncing.
+    ///
+    /// Returns whether the event is held back.
+    fn hold_resize(&mut self, args: &ResizeArgs) -> bool {
+        if self.settled_size.is_none() {return false}
+        self.held_resize = Some((Instant::now(), *args));
+        true
+    }
+
+    /// Settles the size of the held resize event once resize events quieted down.
+    fn release_held_resize(&mut self) {
+        match self.held_resize {
+            Some((at, args)) if at.elapsed() >= self.resize_debounce => {
+                self.held_resize = None;
+                self.settled_size = Some(args.window_size.into());
+                self.observe_resize(&args);
+                self.resize_pending = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the time left before a held resize event is emitted.
+    fn debounce_remaining(&self) -> Option<Duration> {
+        self.held_resize.map(|(at, _)| self.resize_debounce.saturating_sub(at.elapsed()))
+    }
+
+    /// Updates the state that depends on the size of the inner window from a resize event.
+    fn observe_resize(&mut self, args: &ResizeArgs) {
+        if self.check_size_ratio {self.check_resize(args)}
+        if let Some(ref mut last) = self.last_size {*last = args.window_size.into()}
+        self.update_below_threshold(args.window_size.into());
+        self.update_real_dpi(args.window_size, args.draw_size);
+    }
+
+    /// Returns the time left before a throttled resize event can be emitted.
+    fn throttle_remaining(&self) -> Option<Duration> {
+        if !self.resize_pending {return None}
+        let elapsed = self.last_resize?.elapsed();
+        if elapsed < self.resize_throttle {Some(self.resize_throttle - elapsed)} else {None}
+    }
+
+    /// Returns the time until a held back event is due, if any, to wait no longer than that.
+    fn wake_up(&self) -> Option<Duration> {
+        self.throttle_remaining().into_iter()
+            .chain(self.latency_remaining())
+            .chain(self.debounce_remaining())
+            .chain(self.defer_remaining())
+            .min()
+    }
+
+    /// Returns zero while a resize event generated by the wrapper is deferred or due,
+    /// such that waiting keeps counting polls instead of blocking on an idle inner window.
+    fn defer_remaining(&self) -> Option<Duration> {
+        let due = self.resize_pending && !self.suppress_synthetic && self.throttle_remaining().is_none();
+        if due {Some(Duration::ZERO)} else {None}
+    }
+
+    /// Returns the time that events from the inner window are held back.
+    pub fn input_latency(&self) -> Duration {self.input_latency}
+
+    /// Sets the time that events from the inner window are held back, to test latency handling.
+    ///
+    /// Events from the inner window are stamped on arrival and only returned by the polling
+    /// methods once the latency has passed. Waiting for events wakes up in time to return them.
+    /// Resize events generated by the wrapper and injected events are not delayed.
+    /// Since polling drains the inner window into a queue, the queue holds all events
+    /// that arrive within the latency, which grows with the event rate.
+    /// By default, there is no latency.
+    pub fn set_input_latency(&mut self, val: Duration) {self.input_latency = val}
+
+    /// Returns the time left before the next delayed event is due.
+    fn latency_remaining(&self) -> Option<Duration> {
+        let (arrival, _) = self.delayed.front()?;
+        Some((*arrival + self.input_latency).saturating_duration_since(Instant::now()))
+    }
+
+    /// Returns the next delayed event, if it is due.
+    fn release_delayed(&mut self) -> Option<Event> {
+        let (arrival, _) = self.delayed.front()?;
+        if *arrival + self.input_latency > Instant::now() {return None}
+        self.delayed.pop_front().map(|(_, e)| e)
+    }
+
+    /// Returns whether DPI simulation is enabled.
+    pub fn get_enabled(&self) -> bool {self.enabled}
+
+    /// Sets whether DPI simulation is enabled.
+    ///
+    /// When disabled, the wrapper passes coordinates and sizes through unchanged,
+    /// as if the DPI factor was `1.0` and without rounding.
+    /// The DPI configuration is kept, such that it applies again when enabled.
+    /// Changing this emits a resize event.
+    /// By default, this is enabled.
+    pub fn set_enabled(&mut self, val: bool) {
+        if val != self.enabled {
+            self.enabled = val;
+            self.resize_pending = true;
+        }
+    }
+
+    /// Returns the physical size below which the DPI factor is not applied, if enabled.
+    pub fn dpi_threshold(&self) -> Option<Size> {self.dpi_threshold}
+
+    /// Updates whether the window is below the DPI threshold from its physical size.
+    ///
+    /// Returns whether this changed.
+    fn update_below_threshold(&mut self, size: Size) -> bool {
+        let below = match self.dpi_threshold {
+            Some(min) => size.width < min.width || size.height < min.height,
+            None => false,
+        };
+        std::mem::replace(&mut self.below_threshold, below) != below
+    }
+
+    /// Returns the horizontal DPI factor applied to mapping.
+    ///
+    /// This is `dpi()`, or `1.0` while DPI simulation is disabled
+    /// or the window is below the DPI threshold.
+    pub fn effective_dpi(&self) -> f64 {self.scale()[0]}
+
+    /// Returns whether the DPI factor is applied to mapping.
+    fn scaling(&self) -> bool {self.enabled && !self.below_threshold}
+
+    /// Returns the DPI factors used for mapping.
+    fn scale(&self) -> [f64; 2] {
+        if !self.scaling() {return [1.0; 2]}
+        let [dpi, real] = [self.read_dpi(), self.real_scale()];
+        [dpi[0] / real[0], dpi[1] / real[1]]
+    }
+
+    /// Returns the reciprocal DPI factors used for mapping.
+    fn inv_scale(&self) -> [f64; 2] {
+        if !self.scaling() {return [1.0; 2]}
+        let real = self.real_scale();
+        let inv_dpi = if self.has_jitter() {self.read_dpi().map(|dpi| 1.0 / dpi)} else {self.inv_dpi};
+        [inv_dpi[0] * real[0], inv_dpi[1] * real[1]]
+    }
+
+    /// Returns the real DPI factors that are divided out, or one if not compensating.
+    fn real_scale(&self) -> [f64; 2] {
+        if self.compensate_real_dpi {self.real_dpi} else {[1.0; 2]}
+    }
+
+    /// Returns whether the real DPI factor of the inner window is divided out.
+    pub fn get_compensate_real_dpi(&self) -> bool {self.compensate_real_dpi}
+
+    /// Updates the real DPI factors from the size and draw size of the inner window.
+    ///
+    /// Returns whether compensation is enabled and the factors changed.
+    fn update_real_dpi(&mut self, size: [f64; 2], draw_size: [u32; 2]) -> bool {
+        let prev = self.real_dpi;
+        for i in 0..2 {
+            if size[i] > 0.0 && draw_size[i] > 0 {self.real_dpi[i] = draw_size[i] as f64 / size[i]}
+        }
+        self.compensate_real_dpi && prev != self.real_dpi
+    }
+
+    /// Converts a point from logical to physical space.
+    pub fn logical_to_physical(&self, p: [f64; 2]) -> [f64; 2] {scale_point(p, self.scale())}
+
+    /// Converts a point from physical to logical space.
+    pub fn physical_to_logical(&self, p: [f64; 2]) -> [f64; 2] {scale_point(p, self.inv_scale())}
+
+    /// Converts an `f32` point from logical to physical space.
+    ///
+    /// The mapping is computed in `f64` and rounded to the nearest `f32` once,
+    /// so the result is as close as possible to the exact value.
+    /// Note that `f32` represents integers exactly only up to `2^24`,
+    /// and fractional coordinates lose precision well before that.
+    pub fn logical_to_physical_f32(&self, p: [f32; 2]) -> [f32; 2] {
+        let p = self.logical_to_physical([p[0] as f64, p[1] as f64]);
+        [p[0] as f32, p[1] as f32]
+    }
+
+    /// Converts an `f32` point from physical to logical space.
+    ///
+    /// See `logical_to_physical_f32` for the precision of the result.
+    pub fn physical_to_logical_f32(&self, p: [f32; 2]) -> [f32; 2] {
+        let p = self.physical_to_logical([p[0] as f64, p[1] as f64]);
+        [p[0] as f32, p[1] as f32]
+    }
+
+    /// Converts a size from logical to physical space.
+    ///
+    /// The width and height are scaled by the horizontal and vertical DPI factor.
+    /// This is used for `draw_size`.
+    pub fn logical_to_physical_size(&self, size: Size) -> Size {
+        self.logical_to_physical([size.width, size.height]).into()
+    }
+
+    /// Returns the physical size to request from the inner window, such that `size()` reports a logical size.
+    ///
+    /// This is the logical size multiplied by the DPI factor in whole pixels, as used by `set_size`.
+    /// The pixels are picked such that they map back to the same logical size with the rounding mode,
+    /// e.g. `101` logical at `1.5` gives `151` or `152` physical depending on the rounding.
+    /// Without a rounding mode, the round trip is only exact if the scaled size is whole,
+    /// otherwise the nearest pixels are picked.
+    /// If sizes are reported in physical pixels, the size is only rounded to whole pixels.
+    pub fn physical_size_for_logical(&self, logical: Size) -> Size {
+        let size = self.orientation.inverse().size(logical);
+        let size = if self.report_physical_size {to_pixels(size)} else {self.physical_pixels(size)};
+        size.into()
+    }
+
+    /// Returns the draw size reported for a logical window size.
+    ///
+    /// This is the size multiplied by the DPI factor, including a compensated real DPI factor,
+    /// or the ratio of draw size to size of a stacked inner layer,
+    /// such that it matches the pixels of the framebuffer.
+    fn draw_size_of(&self, size: Size, inner: ([f64; 2], [f64; 2])) -> Size {
+        let ratio = if self.stacked {draw_ratio(inner.0, inner.1)} else {self.real_scale()};
+        scale_point(self.logical_to_physical_size(size).into(), ratio).into()
+    }
+
+    /// Converts a size from physical to logical space.
+    ///
+    /// The result is rounded with the rounding mode.
+    /// This is used both for the window size and resize events,
+    /// such that the application never sees two different logical sizes
+    /// for the same physical size.
+    pub fn physical_to_logical_size(&self, size: Size) -> Size {
+        self.round(self.physical_to_logical([size.width, size.height])).into()
+    }
+
+    /// Returns the rounding mode for logical coordinates.
+    pub fn get_rounding(&self) -> RoundingMode {self.rounding}
+
+    /// Sets the rounding mode for logical coordinates.
+    ///
+    /// This applies to the window size, mouse cursor positions,
+    /// scaled mouse scroll and the window size of resize events.
+    /// Relative mouse motion is not rounded, since it is a delta.
+    /// By default, coordinates are not rounded.
+    pub fn set_rounding(&mut self, val: RoundingMode) {self.rounding = val}
+
+    /// Returns the coordinate space of pointer positions.
+    pub fn get_cursor_space(&self) -> CoordinateSpace {self.cursor_space}
+
+    /// Sets the coordinate space of pointer positions.
+    ///
+    /// With `CoordinateSpace::Physical`, mouse cursor positions and relative motion
+    /// are passed through unchanged in framebuffer pixels,
+    /// for rendering code that works in the space of `draw_size`.
+    /// The window size, draw size and resize events are reported as usual,
+    /// so `size()` stays logical and `draw_size()` stays physical.
+    /// By default, pointer positions are logical.
+    pub fn set_cursor_space(&mut self, val: CoordinateSpace) {self.cursor_space = val}
+
+    /// Returns whether mouse cursor positions are clamped to the window bounds.
+    pub fn get_clamp_cursor(&self) -> bool {self.clamp_cursor}
+
+    /// Sets whether mouse cursor positions are clamped to the window bounds.
+    ///
+    /// The bounds are `0.0..=width` and `0.0..=height` of the size reported by `size()`,
+    /// or by `draw_size()` when the cursor space is physical.
+    /// Touch positions are normalized and not affected.
+    /// By default, this is disabled.
+    pub fn set_clamp_cursor(&mut self, val: bool) {self.clamp_cursor = val}
+
+    /// Returns the tolerance for comparing coordinates against the window bounds.
+    pub fn epsilon(&self) -> f64 {self.epsilon.max(0.0)}
+
+    /// Sets the tolerance for comparing coordinates against the window bounds.
+    ///
+    /// Scaling back and forth can leave coordinates a tiny distance off an edge.
+    /// With an epsilon, `contains_physical` counts points up to the epsilon outside
+    /// the bounds as inside, and clamped cursor positions within the epsilon of an edge
+    /// snap to the edge. The epsilon is in the units of the cursor space.
+    /// Monitor edges are compared exactly, such that adjacent monitors never overlap.
+    /// Negative values are treated as zero.
+    /// By default, this is `1e-9`, which absorbs rounding errors but no real offsets.
+    /// Set it to `0.0` to compare exactly.
+    pub fn set_epsilon(&mut self, val: f64) {self.epsilon = val}
+
+    /// Returns whether the vertical axis of pointer positions is flipped.
+    pub fn get_flip_y(&self) -> bool {self.flip_y}
+
+    /// Sets whether the vertical axis of pointer positions is flipped.
+    ///
+    /// This emulates a platform with the origin at the bottom left corner.
+    /// Mouse cursor positions become `(physical_height - y) / dpi`,
+    /// using the current physical height of the inner window,
+    /// and normalized touch positions become `1.0 - y`.
+    /// By default, this is disabled.
+    pub fn set_flip_y(&mut self, val: bool) {self.flip_y = val}
+
+    /// Returns the simulated orientation of the display.
+    pub fn get_orientation(&self) -> Orientation {self.orientation}
+
+    /// Sets the simulated orientation of the display.
+    ///
+    /// The rotation is applied after DPI scaling. Mouse cursor positions, relative motion
+    /// and touch positions are rotated clockwise, and rotations by 90 or 270 degrees
+    /// swap the width and height of the logical window size and resize events,
+    /// e.g. an `800x600` logical window becomes `600x800`.
+    /// The origin and clamping apply to the rotated positions.
+    /// If the width and height are swapped, a resize event is emitted.
+    /// By default, this is `Orientation::Rotate0`.
+    pub fn set_orientation(&mut self, val: Orientation) {
+        if val.swaps_axes() != self.orientation.swaps_axes() {self.resize_pending = true}
+        self.orientation = val;
+    }
+
+    /// Returns whether window sizes are reported in physical pixels.
+    pub fn get_report_physical_size(&self) -> bool {self.report_physical_size}
+
+    /// Sets whether window sizes are reported in physical pixels.
+    ///
+    /// This is a deliberately mismatched test mode for rendering setups that expect
+    /// physical sizes: `size`, `draw_size` and resize events report the physical size
+    /// of the inner window, while pointer positions are still mapped to logical space.
+    /// `set_size` then expects a physical size. Use `logical_size` for the logical size.
+    /// A resize event is emitted when this changes.
+    /// By default, this is off.
+    pub fn set_report_physical_size(&mut self, val: bool) {
+        if val != self.report_physical_size {self.resize_pending = true}
+        self.report_physical_size = val;
+    }
+
+    /// Returns whether mapped values are checked to be finite.
+    pub fn get_strict(&self) -> bool {self.strict}
+
+    /// Sets whether mapped values are checked to be finite.
+    ///
+    /// When enabled, mapping an event to a non-finite position, delta or size panics,
+    /// and so does computing a non-finite logical window size.
+    /// This catches bad values early instead of passing them to the application.
+    /// By default, this is off.
+    pub fn set_strict(&mut self, val: bool) {self.strict = val}
+
+    /// Panics if strict mode is enabled and a mapped value is not finite.
+    fn check_finite(&self, what: &str, val: [f64; 2]) {
+        if self.strict && !(val[0].is_finite() && val[1].is_finite()) {
+            panic!("fake_dpi: mapped {} {:?} is not finite (dpi {}x{})", what, val, self.dpi_x, self.dpi_y);
+        }
+    }
+
+    /// Returns the tolerance for warning about physical sizes passed to `set_size`, if enabled.
+    pub fn size_check_tolerance(&self) -> Option<f64> {self.size_check_tolerance}
+
+    /// Sets the tolerance for warning about physical sizes passed to `set_size`.
+    ///
+    /// When enabled, `set_size` calls the suspicious size callback if the new size is within
+    /// the tolerance of the current logical size multiplied by the DPI factor, which suggests that
+    /// the caller passed a physical size and the window would be scaled twice.
+    /// The tolerance is in logical units. By default, this is `None`.
+    pub fn set_size_check_tolerance(&mut self, val: Option<f64>) {self.size_check_tolerance = val}
+
+    /// Sets a callback that is called when `set_size` receives a size that looks physical.
+    ///
+    /// The callback receives the size passed to `set_size` and the current logical size,
+    /// e.g. to log a warning or fail a test. It is only called while the size check
+    /// tolerance is set, and before the window is resized.
+    pub fn set_suspicious_size_callback(&mut self, callback: Option<SuspiciousSizeCallback>) {
+        self.hooks.suspicious_size_callback = callback;
+    }
+
+    /// Returns the quantum that cursor positions are rounded to, if enabled.
+    pub fn cursor_quantum(&self) -> Option<f64> {
+        self.cursor_quantum.filter(|&quantum| quantum.is_finite() && quantum > 0.0)
+    }
+
+    /// Sets the quantum that cursor positions are rounded to, e.g. `0.25` for quarter pixels.
+    ///
+    /// When set, mapped mouse cursor positions are rounded to the nearest multiple of the quantum
+    /// after scaling and rounding, to simulate the pointer resolution of a platform.
+    /// Touch positions are quantized in the same units, then normalized again.
+    /// This is independent of the rounding mode, so sizes can stay exact.
+    /// A quantum that is not positive and finite is ignored. By default, this is `None`.
+    pub fn set_cursor_quantum(&mut self, val: Option<f64>) {self.cursor_quantum = val}
+
+    /// Returns the physical resolution that incoming pointer coordinates are rounded to, if enabled.
+    pub fn input_precision(&self) -> Option<f64> {
+        self.input_precision.filter(|&precision| precision.is_finite() && precision > 0.0)
+    }
+
+    /// Sets the physical resolution that incoming pointer coordinates are rounded to,
+    /// e.g. `1.0` for whole pixels or `1.0 / 256.0` for 24.8 fixed-point coordinates.
+    ///
+    /// When set, mouse cursor positions and relative motion from the inner window are rounded
+    /// to the nearest multiple of the resolution before any other mapping, to simulate backends
+    /// that lose sub-pixel precision before the application scales the coordinates.
+    /// Unlike the cursor quantum, which applies to logical positions after scaling,
+    /// the lost precision is then divided by the DPI factor.
+    /// A resolution that is not positive and finite is ignored. By default, this is `None`.
+    pub fn set_input_precision(&mut self, val: Option<f64>) {self.input_precision = val}
+
+    /// Rounds incoming pointer coordinates to the input precision, if enabled.
+    fn limit_precision(&self, e: Event) -> Event {
+        use input::Motion::{MouseCursor, MouseRelative};
+
+        let precision = match self.input_precision() {
+            Some(precision) => precision,
+            None => return e,
+        };
+        let limit = |p: [f64; 2]| [quantize(p[0], precision), quantize(p[1], precision)];
+        match e {
+            Event::Input(Input::Move(MouseCursor(pos)), ts) => Event::Input(Input::Move(MouseCursor(limit(pos))), ts),
+            Event::Input(Input::Move(MouseRelative(d)), ts) => Event::Input(Input::Move(MouseRelative(limit(d))), ts),
+            e => e,
+        }
+    }
+
+    /// Returns the logical origin of the content area.
+    pub fn origin(&self) -> [f64; 2] {self.origin}
+
+    /// Sets the logical origin of the content area, e.g. to simulate a title bar or an inset.
+    ///
+    /// The origin is subtracted from mapped mouse cursor and touch positions,
+    /// and from points passed to `contains_physical`.
+    /// For physical cursor positions, the origin is multiplied by the DPI factor first,
+    /// and for normalized touch positions, it is divided by the logical window size.
+    /// By default, this is `[0.0, 0.0]`.
+    pub fn set_origin(&mut self, val: [f64; 2]) {self.origin = val}
+
+    /// Returns the fixed logical size that the window is letterboxed into, if any.
+    pub fn letterbox(&self) -> Option<Size> {
+        self.letterbox.filter(|size| {
+            size.width > 0.0 && size.width.is_finite() && size.height > 0.0 && size.height.is_finite()
+        })
+    }
+
+    /// Sets a fixed logical size that the window is letterboxed into, e.g. `1920x1080` for a game,
+    /// or `None` to map with the DPI factor.
+    ///
+    /// The fixed size is scaled uniformly to the largest area that fits into the physical window size
+    /// and centered, leaving bars on two sides when the aspect ratios differ.
+    /// `size()` and resize events report the fixed size, and the draw size is the physical size
+    /// of the letterboxed area. Mouse cursor positions are mapped relative to the letterboxed area,
+    /// and relative motion and scroll deltas are divided by the scale when they are scaled.
+    /// This replaces the DPI mapping, so the DPI factor, the cursor space, rounding, quantization,
+    /// flipping, rotation, the origin and a custom mapper do not apply to these events.
+    /// Positions over the bars are outside `0.0..size`, which `contains_physical` tells,
+    /// and are clamped to the letterboxed area when clamping is enabled.
+    /// Touch positions stay normalized to the physical window.
+    /// Reporting sizes in physical pixels turns letterboxing off.
+    /// A size that is not positive and finite is ignored. By default, this is `None`.
+    pub fn set_letterbox(&mut self, val: Option<Size>) {self.letterbox = val}
+
+    /// Rounds logical coordinates with the rounding mode.
+    fn round(&self, p: [f64; 2]) -> [f64; 2] {
+        if !self.scaling() {return p}
+        [self.rounding.apply(p[0]), self.rounding.apply(p[1])]
+    }
+
+    /// Converts a logical size to whole physical pixels.
+    ///
+    /// Picks the nearest pixel size that maps back to the same logical size,
+    /// or the nearest pixel size if there is none.
+    fn physical_pixels(&self, size: Size) -> [u32; 2] {
+        let logical = self.round([size.width, size.height]);
+        let physical = self.logical_to_physical([size.width, size.height]);
+        let nearest = [physical[0].round(), physical[1].round()];
+        let other = [0, 1].map(|i| if nearest[i] > physical[i] {nearest[i] - 1.0} else {nearest[i] + 1.0});
+        let maps_back = [self.round(self.physical_to_logical(nearest)), self.round(self.physical_to_logical(other))];
+        [0, 1].map(|i| {
+            let px = if maps_back[0][i] != logical[i] && maps_back[1][i] == logical[i] {other[i]} else {nearest[i]};
+            px as u32
+        })
+    }
+
+    /// Returns whether mouse scroll is scaled by the DPI factor.
+    pub fn get_scale_scroll(&self) -> bool {self.scale_scroll}
+
+    /// Sets whether mouse scroll is scaled by the DPI factor.
+    ///
+    /// Scroll deltas are usually measured in lines or notches, not pixels,
+    /// so they are passed through unchanged by default.
+    /// Enable this to simulate backends that report pixel-precise scrolling.
+    pub fn set_scale_scroll(&mut self, val: bool) {self.scale_scroll = val}
+
+    /// Returns the number of scroll lines per pixel used for paired scroll events, if enabled.
+    pub fn paired_scroll(&self) -> Option<f64> {self.paired_scroll}
+
+    /// Sets the number of scroll lines per pixel used for paired scroll events.
+    ///
+    /// When set, each mouse scroll event is followed by a companion scroll event
+    /// in the other unit, such that both line and pixel scrolling code paths
+    /// can be tested from one input source.
+    /// If mouse scroll is scaled, the scroll event is taken as pixels and the companion
+    /// is multiplied by the factor to get lines, otherwise the scroll event is taken as lines
+    /// and the companion is divided by the factor to get pixels.
+    /// By default, this is `None`.
+    pub fn set_paired_scroll(&mut self, val: Option<f64>) {self.paired_scroll = val}
+
+    /// Returns the companion of a mapped mouse scroll event, if paired scroll is enabled.
+    fn paired_event(&self, e: &Event) -> Option<Event> {
+        let lines_per_pixel = self.paired_scroll?;
+        match *e {
+            Event::Input(Input::Move(input::Motion::MouseScroll(d)), ts) => {
+                let d = if self.scale_scroll {
+                    [d[0] * lines_per_pixel, d[1] * lines_per_pixel]
+                } else {
+                    let pixels_per_line = 1.0 / lines_per_pixel;
+                    [d[0] * pixels_per_line, d[1] * pixels_per_line]
+                };
+                Some(Event::Input(Input::Move(input::Motion::MouseScroll(d)), ts))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the threshold of discrete scroll steps, if enabled.
+    pub fn scroll_step(&self) -> Option<f64> {
+        self.scroll_step.filter(|&step| step > 0.0 && step.is_finite())
+    }
+
+    /// Sets a threshold that converts high-resolution scroll deltas into discrete steps.
+    ///
+    /// When set, mapped scroll deltas are accumulated per axis, and a scroll event
+    /// is only returned when an axis crosses the threshold, with `1.0` or `-1.0`
+    /// for each whole threshold crossed, while the remainder is kept for later events.
+    /// Scroll events that cross no threshold are swallowed. This simulates a mouse wheel
+    /// with notches from a touchpad or a high-resolution wheel, for apps that only
+    /// handle discrete scrolling. Paired scroll events follow the discrete event.
+    /// Setting the threshold clears the accumulated deltas.
+    /// A threshold that is not positive and finite is ignored. By default, this is `None`.
+    pub fn set_scroll_step(&mut self, val: Option<f64>) {
+        self.scroll_step = val;
+        self.scroll_accum = [0.0; 2];
+    }
+
+    /// Converts a mapped scroll event into discrete steps, if enabled.
+    ///
+    /// Returns `None` if the event crosses no threshold.
+    fn discretize_scroll(&mut self, e: Event) -> Option<Event> {
+        use input::Motion::MouseScroll;
+
+        let step = match self.scroll_step() {
+            Some(step) => step,
+            None => return Some(e),
+        };
+        match e {
+            Event::Input(Input::Move(MouseScroll(d)), ts) => {
+                let mut steps = [0.0; 2];
+                for i in 0..2 {
+                    self.scroll_accum[i] += d[i];
+                    steps[i] = (self.scroll_accum[i] / step).trunc();
+                    self.scroll_accum[i] -= steps[i] * step;
+                }
+                if steps == [0.0; 2] {return None}
+                Some(Event::Input(Input::Move(MouseScroll(steps)), ts))
+            }
+            e => Some(e),
+        }
+    }
+
+    /// Returns whether relative mouse motion is scaled by the DPI factor.
+    pub fn get_scale_relative_motion(&self) -> bool {self.scale_relative_motion}
+
+    /// Sets whether relative mouse motion is scaled by the DPI factor.
+    ///
+    /// Disable this to pass relative motion through in raw device units.
+    /// While the cursor is captured with `set_capture_cursor`,
+    /// relative motion is never scaled, since backends then report raw device deltas
+    /// for mouse look, which do not depend on the DPI on real high DPI displays.
+    /// Absolute cursor positions are scaled regardless.
+    /// By default, this is enabled.
+    pub fn set_scale_relative_motion(&mut self, val: bool) {self.scale_relative_motion = val}
+
+    /// Returns whether relative motion is derived from mouse cursor events.
+    pub fn get_derive_relative_motion(&self) -> bool {self.derive_relative_motion}
+
+    /// Sets whether relative motion is derived from mouse cursor events.
+    ///
+    /// When enabled, each mouse cursor event is followed by a relative motion event
+    /// with the delta from the previous mapped cursor position, in the same space
+    /// as the cursor positions, for backends that only report absolute positions.
+    /// The first cursor event after enabling this has no previous position,
+    /// so it is not followed by a relative motion event.
+    /// By default, this is off.
+    pub fn set_derive_relative_motion(&mut self, val: bool) {
+        self.derive_relative_motion = val;
+        self.prev_cursor = None;
+    }
+
+    /// Returns a relative motion event derived from a mapped mouse cursor event, if enabled.
+    fn derived_relative_event(&mut self, e: &Event) -> Option<Event> {
+        use input::Motion::{MouseCursor, MouseRelative};
+
+        if !self.derive_relative_motion {return None}
+        match *e {
+            Event::Input(Input::Move(MouseCursor(pos)), ts) => {
+                let prev = self.prev_cursor.replace(pos)?;
+                Some(Event::Input(Input::Move(MouseRelative([pos[0] - prev[0], pos[1] - prev[1]])), ts))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the input kinds that may be scaled by the DPI factor.
+    pub fn get_scale_mask(&self) -> ScaleMask {self.scale_mask}
+
+    /// Sets the input kinds that may be scaled by the DPI factor.
+    ///
+    /// Events of other kinds are passed through without scaling,
+    /// while flipping, rotation, the origin and clamping still apply.
+    /// By default, mouse cursor positions, relative motion, mouse scroll and resize events
+    /// may be scaled.
+    pub fn set_scale_mask(&mut self, val: ScaleMask) {self.scale_mask = val}
+
+    /// Returns whether resize events from the inner window are checked for mismatched sizes.
+    pub fn get_check_size_ratio(&self) -> bool {self.check_size_ratio}
+
+    /// Sets whether resize events from the inner window are checked for mismatched sizes.
+    ///
+    /// The wrapper expects the inner window to be unaware of high DPI,
+    /// such that its draw size equals its window size,
+    /// or equivalently, the logical window size multiplied by the DPI factor.
+    /// A mismatch of more than one pixel usually means the backend already scales,
+    /// e.g. when running on a real high DPI display, and the scaling is applied twice.
+    /// Mismatches are reported to the size mismatch callback only,
+    /// so enable this together with `set_size_mismatch_callback` to get the warnings.
+    /// By default, this is disabled.
+    pub fn set_check_size_ratio(&mut self, val: bool) {self.check_size_ratio = val}
+
+    /// Sets a callback that is called for mismatched resize events from the inner window.
+    ///
+    /// The callback receives the physical resize arguments,
+    /// e.g. to log a warning or fail a test.
+    /// It is only called while the check is enabled with `set_check_size_ratio`.
+    pub fn set_size_mismatch_callback(&mut self, callback: Option<SizeMismatchCallback>) {
+        self.hooks.size_mismatch_callback = callback;
+    }
+
+    /// Checks that the draw size of a resize event matches its window size.
+    fn check_resize(&mut self, args: &ResizeArgs) {
+        // The mismatch is expected when it is compensated.
+        if self.compensate_real_dpi {return}
+        let f = match self.hooks.size_mismatch_callback {
+            Some(ref mut f) => f,
+            None => return,
+        };
+        let mismatch = (0..2).any(|i| (args.draw_size[i] as f64 - args.window_size[i]).abs() > 1.0);
+        if mismatch {f(args)}
+    }
+
+    /// Sets a callback that is called when the DPI changes.
+    ///
+    /// The callback receives the new horizontal DPI factor.
+    /// It runs synchronously inside the method that changed the DPI,
+    /// and only when a factor actually changed after clamping.
+    pub fn set_dpi_change_callback(&mut self, callback: Option<Box<dyn FnMut(f64)>>) {
+        self.hooks.dpi_change_callback = callback;
+    }
+
+    /// Maps an event from physical to logical space.
+    ///
+    /// # Panics
+    ///
+    /// In strict mode, if a mapped value is not finite.
+    fn map_input(&self, e: Event) -> Event {
+        let e = self.map_input_unchecked(e);
+        if self.strict {
+            use input::Motion::*;
+
+            match e {
+                Event::Input(Input::Move(MouseCursor(p)), _) => self.check_finite("cursor position", p),
+                Event::Input(Input::Move(MouseRelative(p)), _) => self.check_finite("relative motion", p),
+                Event::Input(Input::Move(MouseScroll(p)), _) => self.check_finite("scroll delta", p),
+                Event::Input(Input::Resize(ref args), _) => self.check_finite("window size", args.window_size),
+                _ => {}
+            }
+        }
+        e
+    }
+
+    /// Maps an event from physical to logical space, without checking the result.
+    ///
+    /// Changes to which events are scaled must be reflected in `INPUT_SCALING`.
+    fn map_input_unchecked(&self, e: Event) -> Event {
+        match e {
+            Event::Input(e, ts) if self.scale_mask.allows(InputKind::of(&e)) =>
+                Event::Input(scale_input(self, e), ts),
+            e => e,
+        }
+    }
+
+    /// Sets a custom mapper that replaces the DPI math, or `None` to use the DPI factor.
+    ///
+    /// The mapper receives the physical mouse cursor positions, relative motion,
+    /// scroll deltas, touch events and window sizes that the wrapper would scale,
+    /// so the other options like the scale mask, the cursor space and
+    /// scaling of relative motion and scroll still decide what is mapped.
+    /// Its results are not rounded, while flipping, rotation, the origin and clamping
+    /// still apply around it. `current_transform` does not reflect a custom mapper.
+    pub fn set_coordinate_mapper(&mut self, mapper: Option<Box<dyn CoordinateMapper>>) {
+        self.hooks.coordinate_mapper = mapper;
+    }
+
+    /// Returns the factors that pointer input is divided by.
+    fn input_scale(&self) -> [f64; 2] {
+        match self.input_dpi {
+            Some(dpi) if self.scaling() => {
+                let real = self.real_scale();
+                [dpi / real[0], dpi / real[1]]
+            }
+            _ => self.scale(),
+        }
+    }
+
+    /// Maps a physical window size to logical space, with the custom mapper if set.
+    fn map_size(&self, size: Size) -> Size {
+        match self.hooks.coordinate_mapper {
+            Some(ref mapper) => mapper.map_size(size, self.scale()),
+            None => self.physical_to_logical_size(size),
+        }
+    }
+
+    /// Sets a logger that is called for each event from the inner window.
+    ///
+    /// The logger receives the physical event and the logical event,
+    /// such that they can be compared when diagnosing scaling bugs.
+    /// Events generated by the wrapper itself are not logged.
+    pub fn set_event_logger(&mut self, logger: Option<EventLogger>) {
+        self.hooks.event_logger = logger;
+    }
+
+    /// Sets a transform that is applied to each event after mapping it to logical space.
+    ///
+    /// The transform receives the logical input event and returns the final event,
+    /// e.g. to clamp cursor positions or remap buttons.
+    /// It applies to events from the inner window and injected events,
+    /// but not to events generated by the wrapper itself.
+    /// The event logger receives the transformed event.
+    pub fn set_event_transform(&mut self, transform: Option<EventTransform>) {
+        self.hooks.event_transform = transform;
+    }
+
+    /// Sets a transform that is applied to the time stamps of returned events.
+    ///
+    /// This applies to all events returned by the polling methods,
+    /// e.g. to shift or compress time stamps when testing latency handling.
+    /// Events without a time stamp are not affected.
+    pub fn set_timestamp_transform(&mut self, transform: Option<Box<dyn FnMut(TimeStamp) -> TimeStamp>>) {
+        self.hooks.timestamp_transform = transform;
+    }
+
+    /// Sets a channel that receives a copy of each mapped input event, e.g. for another thread.
+    ///
+    /// The polling methods still return the events as usual, including those generated
+    /// by the wrapper itself. Each returned input event is sent with its time stamp.
+    /// When the receiver is disconnected, the sender is dropped.
+    pub fn set_event_channel(&mut self, sender: Option<Sender<(Input, Option<TimeStamp>)>>) {
+        self.hooks.event_channel = sender;
+    }
+
+    /// Returns the number of buffer swaps after which the window should close.
+    pub fn auto_close_after(&self) -> Option<u32> {self.auto_close_after}
+
+    /// Sets the number of buffer swaps after which the window should close, or `None`.
+    ///
+    /// Once that many `swap_buffers` calls happened since setting this,
+    /// `should_close` returns `true`, which keeps headless test loops bounded.
+    /// The close state of the inner window still applies, so `should_close`
+    /// returns `true` if either the inner window should close or the count is reached.
+    /// By default, there is no limit.
+    pub fn set_auto_close_after(&mut self, val: Option<u32>) {
+        self.auto_close_after = val;
+        self.frames = 0;
+    }
+
+    /// Sets a hook that is called on each `swap_buffers`, before swapping the inner window.
+    ///
+    /// This can be used to measure how often frames are presented.
+    pub fn set_frame_hook(&mut self, hook: Option<Box<dyn FnMut()>>) {
+        self.hooks.frame_hook = hook;
+    }
+
+    /// Sets a hook that is called on each `swap_buffers`, after swapping the inner window,
+    /// right before returning.
+    ///
+    /// The hook receives `size()` and `draw_size()` at that time,
+    /// such that a screenshot of the framebuffer can be taken with the right dimensions.
+    pub fn set_capture_hook(&mut self, hook: Option<CaptureHook>) {
+        self.hooks.capture_hook = hook;
+    }
+
+    /// Sets a hook that is called on each `set_size` with the physical size.
+    ///
+    /// The hook receives the size forwarded to the inner window,
+    /// after mapping it from logical to physical space.
+    pub fn set_size_hook(&mut self, hook: Option<Box<dyn FnMut(Size)>>) {
+        self.hooks.set_size_hook = hook;
+    }
+
+    /// Returns whether statistics about mapped events are collected.
+    pub fn get_collect_event_stats(&self) -> bool {self.event_stats.is_some()}
+
+    /// Sets whether statistics about mapped events are collected.
+    ///
+    /// This counts the events from the inner window and injected events by kind.
+    /// Disabling this discards the statistics.
+    /// By default, this is disabled.
+    pub fn set_collect_event_stats(&mut self, val: bool) {
+        if val != self.event_stats.is_some() {
+            self.event_stats = if val {Some(EventStats::default())} else {None};
+        }
+    }
+
+    /// Returns the statistics about mapped events since the last reset.
+    ///
+    /// All counts are zero when statistics are not collected.
+    pub fn event_stats(&self) -> EventStats {self.event_stats.unwrap_or_default()}
+
+    /// Resets the statistics about mapped events to zero.
+    pub fn reset_event_stats(&mut self) {
+        if let Some(ref mut stats) = self.event_stats {*stats = EventStats::default()}
+    }
+
+    /// Injects an input event in physical space.
+    ///
+    /// Injected events are mapped like events from the inner window,
+    /// and returned by the polling and waiting methods before the inner window is polled or waited on.
+    /// Resize events generated by the wrapper are returned before injected events.
+    pub fn inject_event(&mut self, e: Input) {self.injected.push_back(e)}
+
+    /// Applies the event transform, if any.
+    fn transform_event(&mut self, e: Event) -> Event {
+        match (e, &mut self.hooks.event_transform) {
+            (Event::Input(e, ts), Some(f)) => Event::Input(f(e), ts),
+            (e, _) => e,
+        }
+    }
+
+    /// Sets normalized DPI factors and notifies about the change.
+    fn apply_dpi(&mut self, dpi: [f64; 2]) {
+        // Setting the current factors again is a no-op, e.g. for per-frame slider updates.
+        if dpi == self.dpi_xy() {return}
+        self.dpi_x = Dpi(dpi[0]);
+        self.dpi_y = Dpi(dpi[1]);
+        self.inv_dpi = [1.0 / dpi[0], 1.0 / dpi[1]];
+        self.resize_pending = true;
+        self.deferred_polls = self.resize_defer;
+        if let Some(f) = self.retag_title {f(&mut self.inner, dpi[0])}
+        if let Some(ref mut f) = self.hooks.dpi_change_callback {f(dpi[0])}
+    }
+
+    /// Returns the horizontal and vertical DPI factor.
+    fn dpi_xy(&self) -> [f64; 2] {[self.dpi_x.get(), self.dpi_y.get()]}
+
+    /// Validates the DPI factor, snaps it to the allowed steps and clamps it to the DPI range.
+    fn normalize_dpi(&self, dpi: f64) -> Result<f64, InvalidDpi> {
+        let dpi = validate_dpi(dpi)?;
+        let dpi = self.dpi_steps.iter().copied()
+            .min_by(|a, b| (a - dpi).abs().total_cmp(&(b - dpi).abs()))
+            .unwrap_or(dpi);
+        Ok(dpi.clamp(self.min_dpi, self.max_dpi))
+    }
+}
+
+impl<W: Window> FakeDpiWindow<W> {
+    /// Boxes the wrapper as a `Window` trait object.
+    ///
+    /// Events polled through the trait object are mapped like on the wrapper.
+    /// `AdvancedWindow` requires `Sized` and can not be used as a trait object,
+    /// so the wrapper must be configured before boxing it.
+    pub fn into_boxed(self) -> Box<dyn Window> where W: 'static {Box::new(self)}
+
+    /// Wraps this window in another fake DPI layer, which multiplies the DPI factors.
+    ///
+    /// Unlike wrapping with `new`, the outer layer keeps the ratio of draw size to size
+    /// of this layer, so its draw size is the framebuffer of the innermost window.
+    /// The size ratio check of the outer layer stays disabled, since the mismatch is expected.
+    ///
+    /// # Panics
+    ///
+    /// If the DPI factor is zero, negative or not finite.
+    pub fn stack(self, dpi: f64) -> FakeDpiWindow<FakeDpiWindow<W>> {
+        let mut w = FakeDpiWindow::new(self, dpi);
+        w.stacked = true;
+        w.check_size_ratio = false;
+        w
+    }
+
+    /// Requests the window to close, like when the user clicks the close button.
+    ///
+    /// This sets the close flag of the inner window and queues a close event,
+    /// since many applications only clean up in response to the event.
+    /// The close event is queued like an injected event, so it is returned after
+    /// resize events generated by the wrapper and previously injected events,
+    /// but before any further events from the inner window.
+    pub fn request_close(&mut self) {
+        self.inner.set_should_close(true);
+        self.inject_event(Input::Close(input::CloseArgs));
+    }
+
+    /// Returns whether a size passed to `set_size` looks like a physical size.
+    fn is_suspicious_size(&self, val: Size) -> bool {
+        let tolerance = match self.size_check_tolerance {
+            Some(tolerance) => tolerance,
+            None => return false,
+        };
+        if self.scale() == [1.0; 2] || self.report_physical_size {return false}
+        let physical = self.logical_draw_size();
+        (val.width - physical.width).abs() <= tolerance && (val.height - physical.height).abs() <= tolerance
+    }
+
+    /// Returns the logical window size, as seen by the application.
+    ///
+    /// This is the physical size divided by the DPI factor, rounded with the rounding mode.
+    /// It is the same as `Window::size`, unless sizes are reported in physical pixels.
+    ///
+    /// A minimized window might report a zero size, which gives a zero logical size
+    /// and a zero draw size. Mouse cursor positions are then still mapped,
+    /// but clamped to the origin when clamping is enabled.
+    ///
+    /// # Panics
+    ///
+    /// In strict mode, if the logical size is not finite.
+    pub fn logical_size(&self) -> Size {
+        if let Some((size, _, _)) = self.letterbox_mapping() {return size}
+        let size = self.orientation.size(self.map_size(self.inner_size()));
+        self.check_finite("window size", size.into());
+        size
+    }
+
+    /// Returns the scale from logical to physical pixels and the physical offset
+    /// of the letterboxed area, if letterboxing.
+    ///
+    /// Returns `None` without a letterbox size or when the window has no area.
+    pub fn letterbox_viewport(&self) -> Option<(f64, [f64; 2])> {
+        self.letterbox_mapping().map(|(_, scale, offset)| (scale, offset))
+    }
+
+    /// Returns the letterbox size, the scale and the physical offset, if letterboxing.
+    fn letterbox_mapping(&self) -> Option<(Size, f64, [f64; 2])> {
+        if self.report_physical_size {return None}
+        let target = self.letterbox()?;
+        let size = self.inner_size();
+        let scale = (size.width / target.width).min(size.height / target.height);
+        if scale <= 0.0 {return None}
+        // The fitting dimension can end up a rounding error below zero.
+        let offset = [
+            ((size.width - target.width * scale) * 0.5).max(0.0),
+            ((size.height - target.height * scale) * 0.5).max(0.0),
+        ];
+        Some((target, scale, offset))
+    }
+
+    /// Maps an event into the letterboxed area.
+    fn letterbox_event(&self, e: Event, (size, scale, offset): (Size, f64, [f64; 2])) -> Event {
+        use input::Motion::*;
+
+        let e = match e {
+            Event::Input(e, ts) => (e, ts),
+            e => return e,
+        };
+        Event::Input(match e.0 {
+            e if !self.scale_mask.allows(InputKind::of(&e)) => e,
+            Input::Move(MouseCursor(pos)) => {
+                let pos = [(pos[0] - offset[0]) / scale, (pos[1] - offset[1]) / scale];
+                if !self.clamp_cursor {
+                    Input::Move(MouseCursor(pos))
+                } else {
+                    let eps = self.epsilon();
+                    Input::Move(MouseCursor([
+                        clamp_to_edge(pos[0], size.width, eps),
+                        clamp_to_edge(pos[1], size.height, eps),
+                    ]))
+                }
+            }
+            Input::Move(MouseRelative(pos)) if self.scale_relative_motion && !self.cursor_captured =>
+                Input::Move(MouseRelative([pos[0] / scale, pos[1] / scale])),
+            Input::Move(MouseScroll(pos)) if self.scale_scroll =>
+                Input::Move(MouseScroll([pos[0] / scale, pos[1] / scale])),
+            Input::Resize(_) => Input::Resize(ResizeArgs {
+                window_size: size.into(),
+                draw_size: to_pixels(self.draw_size()),
+            }),
+            e => e,
+        }, e.1)
+    }
+
+    /// Returns the logical window size multiplied by the DPI factor.
+    fn logical_draw_size(&self) -> Size {self.logical_to_physical_size(self.logical_size())}
+
+    /// Returns whether size changes of the inner window are detected without resize events.
+    pub fn get_detect_size_changes(&self) -> bool {self.last_size.is_some()}
+
+    /// Sets whether size changes of the inner window are detected without resize events.
+    ///
+    /// Some backends swallow or coalesce resize events, which leaves the logical size
+    /// seen by the application stale. When enabled, the wrapper remembers the physical size
+    /// of the inner window and emits a resize event if it changed without a resize event.
+    /// The size is compared when the inner window has no more events to poll,
+    /// and before waiting for events.
+    /// By default, this is off.
+    pub fn set_detect_size_changes(&mut self, val: bool) {
+        self.last_size = if val {Some(self.inner_size())} else {None};
+    }
+
+    /// Sets whether the real DPI factor of the inner window is divided out.
+    ///
+    /// When running on a real Hi-DPI screen with a backend that already scales,
+    /// coordinates would be scaled twice, by the real and the fake factor.
+    /// With compensation, the mapping divides by the fake factor over the real factor,
+    /// such that the net scaling from framebuffer pixels is only the fake factor,
+    /// and the draw size is the logical size multiplied by the fake factor.
+    /// The `Window` trait does not expose a scale factor, so the real factor is detected
+    /// as the ratio of draw size to size of the inner window,
+    /// both when enabling this and on resize events of the inner window.
+    /// Backends that report the same draw size and size give one, which makes this a no-op.
+    /// This also silences the size ratio check, since the mismatch is expected.
+    /// Changing this emits a resize event if the real factor is not one.
+    /// By default, this is off.
+    pub fn set_compensate_real_dpi(&mut self, val: bool) {
+        let size = self.inner_size();
+        self.update_real_dpi(size.into(), to_pixels(self.inner.draw_size()));
+        if val != self.compensate_real_dpi && self.real_dpi != [1.0; 2] {self.resize_pending = true}
+        self.compensate_real_dpi = val;
+    }
+
+    /// Sets the physical size below which the DPI factor is not applied.
+    ///
+    /// This emulates platforms that only scale windows that are large enough.
+    /// While the inner window is narrower or lower than the threshold,
+    /// the wrapper maps as if the DPI factor was `1.0` and without rounding,
+    /// like when DPI simulation is disabled.
+    /// The window size is checked against the threshold on resize events of the inner window,
+    /// so the resize event that crosses the threshold is mapped with the new effective factor.
+    /// Changing the threshold emits a resize event if it changes the effective factor.
+    /// By default, this is `None`.
+    pub fn set_dpi_threshold(&mut self, val: Option<Size>) {
+        self.dpi_threshold = val;
+        let size = self.inner_size();
+        if self.update_below_threshold(size) {self.resize_pending = true}
+    }
+
+    /// Sets a quiet interval for debouncing resize events of the inner window, or zero to disable it.
+    ///
+    /// This simulates a window manager that reports the size lazily during a live resize.
+    /// While enabled, each resize event of the inner window is held back, and `size()`
+    /// keeps reporting the last settled size. Once no resize event arrived for the interval,
+    /// the size of the latest held event settles and a single resize event is emitted,
+    /// so a storm of resize events ends in one event with the final size.
+    /// The intermediate events are discarded. Resize events generated by the wrapper, e.g. after
+    /// DPI changes, are not debounced, but report the settled size.
+    /// Disabling it settles a held resize event right away. By default, this is off.
+    pub fn set_resize_debounce(&mut self, val: Duration) {
+        self.resize_debounce = val;
+        if let Some((_, args)) = self.held_resize.take() {
+            self.observe_resize(&args);
+            self.resize_pending = true;
+        }
+        self.settled_size = None;
+        if !val.is_zero() {self.settled_size = Some(self.inner_size())}
+    }
+
+    /// Schedules a resize event if the size of the inner window changed since last seen.
+    ///
+    /// Returns whether a resize event was scheduled.
+    fn detect_size_change(&mut self) -> bool {
+        let size = self.inner_size();
+        match self.last_size {
+            Some(ref mut last) if *last != size => {
+                *last = size;
+                self.update_below_threshold(size);
+                self.resize_pending = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the physical window size of the inner window.
+    ///
+    /// Negative or `NaN` dimensions are reported as zero.
+    pub fn physical_size(&self) -> Size {self.inner_size()}
+
+    /// Returns the size of the inner window, with degenerate dimensions as zero.
+    ///
+    /// Some platforms report odd sizes for minimized windows,
+    /// which would otherwise turn into `NaN` coordinates or panics when clamping.
+    fn inner_size(&self) -> Size {
+        let size = self.settled_size.unwrap_or_else(|| self.inner.size());
+        Size {width: size.width.max(0.0), height: size.height.max(0.0)}
+    }
+
+    /// Returns the logical size the window would report with a DPI factor, without setting it.
+    ///
+    /// The DPI factor is clamped to the DPI range and the result is rounded
+    /// with the rounding mode, like `size()` after calling `set_dpi`.
+    ///
+    /// # Panics
+    ///
+    /// If the DPI factor is zero, negative or not finite.
+    pub fn size_at_dpi(&self, dpi: f64) -> Size {
+        let dpi = match self.normalize_dpi(dpi) {
+            Ok(dpi) => dpi,
+            Err(err) => panic!("{}", err),
+        };
+        let size = self.orientation.size(self.inner_size());
+        if !self.scaling() {return size}
+        let inv_dpi = 1.0 / dpi;
+        let real = self.real_scale();
+        let size = [size.width * inv_dpi * real[0], size.height * inv_dpi * real[1]];
+        [self.rounding.apply(size[0]), self.rounding.apply(size[1])].into()
+    }
+
+    /// Returns the logical window size in whole units.
+    ///
+    /// This is `size()` rounded with the rounding mode, or to the nearest integer
+    /// without a rounding mode, with negative values clamped to zero.
+    pub fn size_u32(&self) -> [u32; 2] {self.to_u32(self.size())}
+
+    /// Returns the draw size in whole pixels.
+    ///
+    /// This is `draw_size()` rounded like `size_u32`.
+    pub fn draw_size_u32(&self) -> [u32; 2] {self.to_u32(self.draw_size())}
+
+    /// Rounds a size to integers with the rounding mode.
+    fn to_u32(&self, size: Size) -> [u32; 2] {
+        let round = |val: f64| match self.rounding {
+            RoundingMode::None => val.round(),
+            mode => mode.apply(val),
+        }.max(0.0) as u32;
+        [round(size.width), round(size.height)]
+    }
+
+    /// Returns whether a physical point is inside the window after mapping.
+    ///
+    /// The point is mapped like a mouse cursor position and compared against `size()`,
+    /// or `draw_size()` when the cursor space is physical.
+    /// The bounds are half-open, such that `0.0` is inside and the width is outside.
+    /// With an epsilon, points up to the epsilon outside the bounds are inside,
+    /// including points exactly on the right and bottom edges.
+    /// Since the default epsilon is not zero, set it to `0.0` for strictly half-open bounds.
+    pub fn contains_physical(&self, p: [f64; 2]) -> bool {
+        let eps = self.epsilon();
+        if let Some((size, scale, offset)) = self.letterbox_mapping() {
+            let p = [(p[0] - offset[0]) / scale, (p[1] - offset[1]) / scale];
+            return p[0] >= -eps && p[0] < size.width + eps && p[1] >= -eps && p[1] < size.height + eps
+        }
+        let (p, bounds) = match self.cursor_space {
+            CoordinateSpace::Logical => (self.round(self.input_to_logical(p)), self.logical_size()),
+            CoordinateSpace::Physical => (p, self.logical_draw_size()),
+        };
+        let p = self.orientation.point(p, self.unrotated_cursor_bounds());
+        let origin = self.cursor_origin();
+        let p = [p[0] - origin[0], p[1] - origin[1]];
+        p[0] >= -eps && p[0] < bounds.width + eps && p[1] >= -eps && p[1] < bounds.height + eps
+    }
+
+    /// Returns a human-readable report of the mapping options and derived values, e.g. for bug reports.
+    ///
+    /// Unlike `Debug`, this includes computed values like `size()`, `draw_size()`
+    /// and the current cursor transform. The format is meant for reading and may change.
+    pub fn diagnostics(&self) -> String {
+        use std::fmt::Write;
+
+        let dpi = self.dpi_xy();
+        let physical = self.inner_size();
+        let (size, draw_size) = (self.size(), self.draw_size());
+        let mut s = String::new();
+        let _ = writeln!(s, "dpi: {}x{} (range {}..={}, effective {:?})",
+                         dpi[0], dpi[1], self.min_dpi, self.max_dpi, self.scale());
+        let _ = writeln!(s, "input dpi: {:?}", self.input_dpi);
+        let _ = writeln!(s, "enabled: {}, below threshold: {}, report physical size: {}",
+                         self.enabled, self.below_threshold, self.report_physical_size);
+        let _ = writeln!(s, "rounding: {:?}, cursor space: {:?}", self.rounding, self.cursor_space);
+        let _ = writeln!(s, "clamp cursor: {}, epsilon: {:e}", self.clamp_cursor, self.epsilon());
+        let _ = writeln!(s, "origin: {:?}, flip y: {}, orientation: {:?}",
+                         self.origin, self.flip_y, self.orientation);
+        let _ = writeln!(s, "input precision: {:?}, cursor quantum: {:?}",
+                         self.input_precision(), self.cursor_quantum());
+        let _ = writeln!(s, "physical size: {}x{}", physical.width, physical.height);
+        let _ = writeln!(s, "logical size: {}x{}", size.width, size.height);
+        let _ = writeln!(s, "draw size: {}x{}", draw_size.width, draw_size.height);
+        if let Some((scale, offset)) = self.letterbox_viewport() {
+            let _ = writeln!(s, "letterbox: scale {}, offset {:?}", scale, offset);
+        }
+        let _ = writeln!(s, "cursor transform: {:?}", self.current_transform());
+        s
+    }
+
+    /// Returns the current mapping of mouse cursor positions, to apply it elsewhere.
+    ///
+    /// The result reflects the current settings, so it should be queried again
+    /// after changing the DPI factor, the window size or any mapping option.
+    /// Letterboxing is not reflected.
+    pub fn current_transform(&self) -> CursorTransform {
+        let scaled = self.cursor_space == CoordinateSpace::Logical &&
+                     self.scale_mask.contains(ScaleMask::CURSOR);
+        let clamp = if self.clamp_cursor {
+            let bounds = match self.cursor_space {
+                CoordinateSpace::Logical => self.logical_size(),
+                CoordinateSpace::Physical => self.logical_draw_size(),
+            };
+            Some(bounds.into())
+        } else {
+            None
+        };
+        CursorTransform {
+            precision: self.input_precision(),
+            flip_height: if self.flip_y {Some(self.inner_size().height)} else {None},
+            scale: if scaled {self.input_to_logical([1.0; 2])} else {[1.0; 2]},
+            rounding: if scaled && self.scaling() {self.rounding} else {RoundingMode::None},
+            quantum: self.cursor_quantum(),
+            orientation: self.orientation,
+            rotation_bounds: self.unrotated_cursor_bounds(),
+            origin: self.cursor_origin(),
+            clamp,
+            epsilon: self.epsilon(),
+        }
+    }
+
+    /// Maps a physical cursor position, e.g. from a global hook, like a mouse cursor event.
+    ///
+    /// This applies the same flipping, scaling, rounding, quantization, rotation, origin and clamping
+    /// as the event path, but not the event transform.
+    pub fn map_cursor_physical(&self, p: [f64; 2]) -> [f64; 2] {
+        let e = Event::Input(Input::Move(input::Motion::MouseCursor(p)), None);
+        match self.map_cursor(e) {
+            Event::Input(Input::Move(input::Motion::MouseCursor(p)), _) => p,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Maps a cursor position returned by the wrapper back to a physical position.
+    ///
+    /// This is the inverse of `map_cursor_physical`, except that
+    /// rounding and clamping can not be undone.
+    pub fn unmap_cursor_logical(&self, p: [f64; 2]) -> [f64; 2] {
+        if let Some((_, scale, offset)) = self.letterbox_mapping() {
+            return [p[0] * scale + offset[0], p[1] * scale + offset[1]]
+        }
+        let origin = self.cursor_origin();
+        let p = [p[0] + origin[0], p[1] + origin[1]];
+        let bounds = self.orientation.size(self.unrotated_cursor_bounds().into()).into();
+        let p = self.orientation.inverse().point(p, bounds);
+        let p = match self.cursor_space {
+            CoordinateSpace::Logical => self.input_to_physical(p),
+            CoordinateSpace::Physical => p,
+        };
+        if self.flip_y {[p[0], self.inner_size().height - p[1]]} else {p}
+    }
+
+    /// Returns the logical bounding box of physical points, as `(min_x, min_y, max_x, max_y)`.
+    ///
+    /// Each point is mapped with `map_cursor_physical`, e.g. to check where a scaled
+    /// multi-touch gesture or a selection lands. Returns `None` for no points.
+    pub fn logical_bounds(&self, points: &[[f64; 2]]) -> Option<(f64, f64, f64, f64)> {
+        let mut points = points.iter().map(|&p| self.map_cursor_physical(p));
+        let first = points.next()?;
+        Some(points.fold((first[0], first[1], first[0], first[1]), |(x0, y0, x1, y1), p| {
+            (x0.min(p[0]), y0.min(p[1]), x1.max(p[0]), y1.max(p[1]))
+        }))
+    }
+
+    /// Maps a batch of input events in physical space, e.g. a captured stream, to logical space.
+    ///
+    /// The result is identical to injecting the events and polling until there are none left,
+    /// without polling the inner window. All stages of polling apply, so this has the same effects,
+    /// e.g. DPI hotkeys and monitor switching change the DPI factor for the following events,
+    /// events are counted, logged and recorded, and the event transform is called.
+    /// Events generated by the wrapper, like resize events and paired scroll events,
+    /// are included in order, and swallowed events are left out,
+    /// so the result may have a different number of events. Pausing is ignored.
+    pub fn map_events(&mut self, events: &[Input]) -> Vec<Input> {
+        self.map_batch(events).into_iter().map(|(e, _)| e).collect()
+    }
+
+    /// Maps a batch of input events like `map_events`, and reports which events changed.
+    ///
+    /// Each mapped event is paired with whether it differs from the input event it was mapped from,
+    /// compared with exact equality, e.g. to highlight affected events in a visualization.
+    /// An event that is mapped to the same value, like a cursor position at the origin,
+    /// is reported as unchanged. Events generated by the wrapper are reported as changed.
+    pub fn map_events_diff(&mut self, events: &[Input]) -> Vec<(Input, bool)> {
+        self.map_batch(events).into_iter()
+            .map(|(mapped, e)| {
+                let changed = e.is_none_or(|e| mapped != *e);
+                (mapped, changed)
+            })
+            .collect()
+    }
+
+    /// Maps a batch of input events like polling,
+    /// pairing each event with the input event it was mapped from, if any.
+    fn map_batch<'a>(&mut self, events: &'a [Input]) -> Vec<(Input, Option<&'a Input>)> {
+        let mut mapped = vec![];
+        let mut events = events.iter();
+        loop {
+            // Events generated by the wrapper come first, like when polling.
+            let (e, source) = match self.pending_event() {
+                Some(e) => (Some(e), None),
+                None => match events.next() {
+                    Some(source) => (self.process_event(Event::Input(source.clone(), None)), Some(source)),
+                    None => return mapped,
+                }
+            };
+            if let Some(Event::Input(e, _)) = e.map(|e| self.deliver(e)) {mapped.push((e, source))}
+        }
+    }
+
+    /// Maps pointer positions of an event from the inner window, without the event transform.
+    fn map_cursor(&self, e: Event) -> Event {
+        let e = self.limit_precision(e);
+        if let Some(mapping) = self.letterbox_mapping() {return self.letterbox_event(e, mapping)}
+        let e = self.quantize_event(self.map_input(self.flip_event(e)));
+        self.clamp_cursor(self.offset_event(self.rotate_event(e)))
+    }
+
+    /// Returns whether the window is visible.
+    ///
+    /// This tracks calls to `show` and `hide` through the wrapper,
+    /// assuming that the inner window is visible when wrapped.
+    pub fn is_visible(&self) -> bool {self.visible}
+
+    /// Hides the window right after building or wrapping it.
+    ///
+    /// This simulates a window that starts hidden, to test initialization logic
+    /// that runs before the first `show`, like `FakeDpiWindowSettings::start_hidden`.
+    /// This method moves the window and returns it.
+    pub fn start_hidden(mut self) -> Self where W: AdvancedWindow {
+        self.hide();
+        self
+    }
+
+    /// Returns whether the window title suffix is updated with the DPI factor.
+    pub fn get_tag_title(&self) -> bool {self.retag_title.is_some()}
+
+    /// Sets whether the window title suffix is updated with the DPI factor.
+    ///
+    /// When enabled, the title gets a suffix like ` (fake dpi 2x)`,
+    /// which is replaced whenever the DPI factor changes, and appended to titles
+    /// set through the wrapper. A previous suffix is stripped first, so suffixes
+    /// never accumulate, e.g. when building with `tag_title` and enabling this too.
+    /// When disabled, the suffix is removed.
+    /// This requires `AdvancedWindow`, so it can not be enabled by the window settings.
+    /// By default, this is off.
+    pub fn set_tag_title(&mut self, val: bool) where W: AdvancedWindow {
+        if val {
+            self.retag_title = Some(retag_title::<W>);
+            retag_title(&mut self.inner, self.dpi_x.get());
+        } else {
+            self.retag_title = None;
+            let title = self.inner.get_title();
+            self.inner.set_title(settings::strip_title_suffix(&title).to_string());
+        }
+    }
+
+    /// Returns the last mapped input event returned from a polling method, if any.
+    pub fn last_event(&self) -> Option<&Input> {self.last_event.as_ref()}
+
+    /// Returns the last resize event from the inner window and its mapped form, if any.
+    ///
+    /// This makes the mapping of the window size and draw size observable without a logger.
+    /// The mapped form is recorded before the event transform.
+    /// Resize events generated by the wrapper have no incoming form and are not recorded.
+    pub fn last_resize_mapping(&self) -> Option<(ResizeArgs, ResizeArgs)> {self.last_resize_mapping}
+
+    /// Returns the last mouse cursor position seen from the inner window or injected, if any.
+    ///
+    /// The `Window` trait has no way to query the cursor position outside the event stream,
+    /// so this is the position of the last mouse cursor event, before any mapping.
+    pub fn last_cursor_physical(&self) -> Option<[f64; 2]> {self.last_cursor}
+
+    /// Returns the last mouse cursor position in logical space, if any.
+    ///
+    /// This maps `last_cursor_physical` like `map_cursor_physical`, with the current
+    /// DPI factor and options, so it follows DPI changes without waiting for a move event.
+    pub fn last_cursor_logical(&self) -> Option<[f64; 2]> {
+        self.last_cursor.map(|p| self.map_cursor_physical(p))
+    }
+
+    /// Returns whether the last resize event returned from a polling method was
+    /// generated by the wrapper, or `None` if no resize event was returned yet.
+    ///
+    /// Resize events are generated after DPI changes, for the initial resize
+    /// and when size changes are detected. Resize events from the inner window,
+    /// injected or replayed resize events are not synthetic.
+    pub fn last_resize_was_synthetic(&self) -> Option<bool> {self.last_resize_synthetic}
+
+    /// Maps an event from the inner window.
+    ///
+    /// Returns `None` if the event is swallowed.
+    fn process_event(&mut self, e: Event) -> Option<Event> {
+        if let Event::Input(Input::Button(ButtonArgs {button: Button::Keyboard(key), state, ..}), _) = e {
+            if Some(key) == self.dpi_hotkey {
+                if state == ButtonState::Press {
+                    let dpi = self.dpi_x.get() + self.dpi_hotkey_step;
+                    // A step that leaves no positive DPI factor is ignored.
+                    let _ = self.set_dpi(dpi);
+                }
+                if self.swallow_dpi_hotkey {return None}
+            }
+        }
+        if let Event::Input(Input::Move(input::Motion::MouseCursor(pos)), _) = e {
+            self.last_cursor = Some(pos);
+            let origin = self.window_position.map_or([0, 0], |(_, p)| [p.x, p.y]);
+            if let Some(idx) = self.monitor_at([pos[0] + origin[0] as f64, pos[1] + origin[1] as f64]) {
+                if Some(idx) != self.active_monitor {
+                    let _ = self.set_active_monitor(idx);
+                }
+            }
+        }
+        if let Event::Input(Input::Resize(ref args), _) = e {
+            if self.hold_resize(args) {return None}
+            self.observe_resize(args);
+        }
+        if let (Some(stats), Event::Input(e, _)) = (&mut self.event_stats, &e) {stats.count(e)}
+        let raw = if self.hooks.event_logger.is_some() {Some(e.clone())} else {None};
+        let incoming = if let Event::Input(Input::Resize(args), _) = e {Some(args)} else {None};
+        let e = self.map_cursor(e);
+        if let (Some(incoming), Event::Input(Input::Resize(outgoing), _)) = (incoming, &e) {
+            self.last_resize_mapping = Some((incoming, *outgoing));
+        }
+        let e = self.discretize_scroll(e)?;
+        let e = self.transform_event(e);
+        self.paired = self.paired_event(&e).or_else(|| self.derived_relative_event(&e));
+        if let (Some(Event::Input(raw, _)), Event::Input(mapped, _)) = (&raw, &e) {
+            if let Some(ref mut f) = self.hooks.event_logger {f(raw, mapped)}
+        }
+        Some(e)
+    }
+
+    /// Flips the vertical axis of mouse cursor and touch positions, if enabled.
+    fn flip_event(&self, e: Event) -> Event {
+        use input::Motion::{MouseCursor, Touch};
+
+        if !self.flip_y {return e}
+        match e {
+            Event::Input(Input::Move(MouseCursor(pos)), ts) => {
+                let height = self.inner_size().height;
+                Event::Input(Input::Move(MouseCursor([pos[0], height - pos[1]])), ts)
+            }
+            Event::Input(Input::Move(Touch(mut args)), ts) => {
+                // Only the position changes, other touch fields are kept as they are.
+                args.position_3d[1] = 1.0 - args.position_3d[1];
+                Event::Input(Input::Move(Touch(args)), ts)
+            }
+            e => e,
+        }
+    }
+
+    /// Returns the unrotated window bounds in the coordinate space of cursor positions.
+    fn unrotated_cursor_bounds(&self) -> [f64; 2] {
+        let size = self.map_size(self.inner_size());
+        match self.cursor_space {
+            CoordinateSpace::Logical => size.into(),
+            CoordinateSpace::Physical => self.logical_to_physical_size(size).into(),
+        }
+    }
+
+    /// Rotates mapped mouse cursor positions, relative motion and touch positions.
+    fn rotate_event(&self, e: Event) -> Event {
+        use input::Motion::{MouseCursor, MouseRelative, Touch};
+
+        let o = self.orientation;
+        if o == Orientation::Rotate0 {return e}
+        match e {
+            Event::Input(Input::Move(MouseCursor(pos)), ts) =>
+                Event::Input(Input::Move(MouseCursor(o.point(pos, self.unrotated_cursor_bounds()))), ts),
+            Event::Input(Input::Move(MouseRelative(d)), ts) =>
+                Event::Input(Input::Move(MouseRelative(o.point(d, [0.0; 2]))), ts),
+            Event::Input(Input::Move(Touch(mut args)), ts) => {
+                // Touch positions are normalized, so the bounds are one.
+                let p = o.point([args.position_3d[0], args.position_3d[1]], [1.0; 2]);
+                args.position_3d[0] = p[0];
+                args.position_3d[1] = p[1];
+                Event::Input(Input::Move(Touch(args)), ts)
+            }
+            e => e,
+        }
+    }
+
+    /// Rounds mouse cursor and touch positions to the cursor quantum, if set.
+    fn quantize_event(&self, e: Event) -> Event {
+        use input::Motion::{MouseCursor, Touch};
+
+        let quantum = match self.cursor_quantum() {
+            Some(quantum) => quantum,
+            None => return e,
+        };
+        match e {
+            Event::Input(Input::Move(MouseCursor(pos)), ts) =>
+                Event::Input(Input::Move(MouseCursor([quantize(pos[0], quantum), quantize(pos[1], quantum)])), ts),
+            Event::Input(Input::Move(Touch(mut args)), ts) => {
+                // Touch positions are normalized, so they are quantized in window units.
+                let bounds = self.unrotated_cursor_bounds();
+                for (pos, &size) in args.position_3d.iter_mut().zip(&bounds) {
+                    if size > 0.0 {*pos = quantize(*pos * size, quantum) / size}
+                }
+                Event::Input(Input::Move(Touch(args)), ts)
+            }
+            e => e,
+        }
+    }
+
+    /// Returns the origin in the coordinate space of cursor positions.
+    fn cursor_origin(&self) -> [f64; 2] {
+        match self.cursor_space {
+            CoordinateSpace::Logical => self.origin,
+            CoordinateSpace::Physical => self.logical_to_physical(self.origin),
+        }
+    }
+
+    /// Subtracts the origin from mapped mouse cursor and touch positions.
+    fn offset_event(&self, e: Event) -> Event {
+        use input::Motion::{MouseCursor, Touch};
+
+        if self.origin == [0.0; 2] {return e}
+        match e {
+            Event::Input(Input::Move(MouseCursor(pos)), ts) => {
+                let origin = self.cursor_origin();
+                Event::Input(Input::Move(MouseCursor([pos[0] - origin[0], pos[1] - origin[1]])), ts)
+            }
+            Event::Input(Input::Move(Touch(mut args)), ts) => {
+                // Only the position changes, other touch fields like the pressure,
+                // device, id and depth are normalized or opaque and kept as they are.
+                let size = self.logical_size();
+                if size.width > 0.0 {args.position_3d[0] -= self.origin[0] / size.width}
+                if size.height > 0.0 {args.position_3d[1] -= self.origin[1] / size.height}
+                Event::Input(Input::Move(Touch(args)), ts)
+            }
+            e => e,
+        }
+    }
+
+    /// Clamps mapped mouse cursor positions to the window bounds, if enabled.
+    fn clamp_cursor(&self, e: Event) -> Event {
+        use input::Motion::MouseCursor;
+
+        match e {
+            Event::Input(Input::Move(MouseCursor(pos)), ts) if self.clamp_cursor => {
+                let bounds = match self.cursor_space {
+                    CoordinateSpace::Logical => self.logical_size(),
+                    CoordinateSpace::Physical => self.logical_draw_size(),
+                };
+                let eps = self.epsilon();
+                Event::Input(Input::Move(MouseCursor([
+                    clamp_to_edge(pos[0], bounds.width, eps),
+                    clamp_to_edge(pos[1], bounds.height, eps),
+                ])), ts)
+            }
+            e => e,
+        }
+    }
+
+    /// Polls an event from the inner window without any mapping.
+    ///
+    /// The event keeps its physical coordinates and time stamp, for tools that need
+    /// device-native input. Events buffered by `has_pending_events` or while paused
+    /// are returned first. This ignores whether delivery is paused, but not the input latency.
+    /// Resize events generated by the wrapper, injected and replayed events are not returned,
+    /// but stay queued for `poll_event`.
+    /// No hooks, statistics or DPI hotkeys apply to the event.
+    pub fn poll_raw_event(&mut self) -> Option<Event> {
+        self.buffered.pop_front().or_else(|| self.poll_inner())
+    }
+
+    /// Polls an event, returning both its physical and logical form with the time stamp.
+    ///
+    /// This behaves like `poll_event`, but also returns the input event before mapping.
+    /// Events generated by the wrapper itself, like resize events after a DPI change,
+    /// have no physical form and are returned unchanged in both.
+    /// Events that are not input events are skipped, except an event from `peek_event`,
+    /// which is kept for the next poll while this returns `None`.
+    pub fn poll_event_raw(&mut self) -> Option<(Input, Input, Option<TimeStamp>)> {
+        if self.buffer_while_paused() {return None}
+        if let Some(pair) = self.peeked.take() {
+            if let (Event::Input(raw, _), Event::Input(e, ts)) = pair {return Some((raw, e, ts))}
+            self.peeked = Some(pair);
+            return None
+        }
+        loop {
+            if let (Event::Input(raw, _), Event::Input(e, ts)) = self.poll_with_raw()? {
+                return Some((raw, e, ts))
+            }
+        }
+    }
+
+    /// Polls an event like `poll_event`, returning it before and after mapping.
+    fn poll_with_raw(&mut self) -> Option<(Event, Event)> {
+        if self.buffer_while_paused() {return None}
+        loop {
+            if let Some(e) = self.pending_event() {return Some((e.clone(), self.deliver(e)))}
+            let raw = match self.poll_unmapped() {
+                Some(e) => e,
+                None if self.detect_size_change() => continue,
+                None => return None,
+            };
+            if let Some(e) = self.process_event(raw.clone()) {return Some((raw, self.deliver(e)))}
+        }
+    }
+
+    /// Returns the next mapped input event without consuming it.
+    ///
+    /// The event is polled like with `poll_event`, in the same order of events generated by the wrapper,
+    /// injected events and events from the inner window, and kept in a one-slot look-ahead buffer.
+    /// Peeking again and the next polling or waiting method return the same event,
+    /// so the inner window is not polled twice, e.g. for an inspector that observes events.
+    /// `poll_event_raw` also returns the event before mapping, and while delivery is paused,
+    /// only `wait_event` returns the event.
+    /// Hooks, statistics and recording apply once, when the event is peeked first.
+    /// Returns `None` if there is no event or the next event is not an input event,
+    /// which is still returned by the next poll.
+    pub fn peek_event(&mut self) -> Option<(Input, Option<TimeStamp>)> {
+        if self.peeked.is_none() {self.peeked = self.poll_with_raw()}
+        match self.peeked {
+            Some((_, Event::Input(ref e, ts))) => Some((e.clone(), ts)),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator that polls mapped input events until there are none left.
+    ///
+    /// Events generated by the wrapper and injected events come first, like with `poll_event`.
+    /// The iterator stops at the first `None` from polling.
+    /// Events that are not input events are skipped.
+    pub fn poll_iter(&mut self) -> impl Iterator<Item = (Input, Option<TimeStamp>)> + '_ {
+        std::iter::from_fn(move || loop {
+            if let Event::Input(e, ts) = self.poll_event()? {return Some((e, ts))}
+        })
+    }
+
+    /// Polls an event and converts it into a generic event type.
+    ///
+    /// The event is mapped to logical space before the conversion.
+    /// Since `GenericEvent` converts from `Input` and `Loop`,
+    /// time stamps are dropped and custom events are skipped.
+    pub fn poll_generic_event<E: GenericEvent>(&mut self) -> Option<E> {
+        loop {
+            match self.poll_event()? {
+                Event::Input(e, _) => return Some(e.into()),
+                Event::Loop(e) => return Some(e.into()),
+                Event::Custom(..) => {}
+            }
+        }
+    }
+
+    /// Suppresses events that do not come from the inner window until the guard is dropped.
+    ///
+    /// While the guard is alive, the polling and waiting methods skip resize events
+    /// generated by the wrapper, injected and replayed events, and only return events
+    /// from the inner window, e.g. to drain them when setting up a test.
+    /// The skipped events are not discarded, but stay queued until the guard is dropped.
+    pub fn suppress_synthetic_events(&mut self) -> SuppressSyntheticEvents<'_, W> {
+        self.suppress_synthetic = true;
+        SuppressSyntheticEvents {window: self}
+    }
+
+    /// Returns whether event delivery is paused.
+    pub fn get_paused(&self) -> bool {self.paused}
+
+    /// Sets whether event delivery is paused, e.g. for step-debugging.
+    ///
+    /// While paused, polling returns `None` and `wait_event_timeout` returns `None` after waiting,
+    /// while events from the inner window are buffered, such that none are lost.
+    /// Resize events generated by the wrapper, injected and replayed events stay queued.
+    /// When resumed, all events are delivered in their usual order, mapped at that time.
+    /// `wait_event` ignores the pause, because it could never return otherwise.
+    /// By default, this is off.
+    pub fn set_paused(&mut self, val: bool) {self.paused = val}
+
+    /// Buffers all events of the inner window, if paused.
+    ///
+    /// Returns whether delivery is paused.
+    fn buffer_while_paused(&mut self) -> bool {
+        if !self.paused {return false}
+        while let Some(e) = self.poll_inner() {self.buffered.push_back(e)}
+        true
+    }
+
+    /// Returns whether an event is available without waiting.
+    ///
+    /// If there are no events generated by the wrapper, injected or replayed,
+    /// this polls the inner window and buffers the event, which is returned next.
+    /// Injected events are still returned before the buffered event,
+    /// like before any other event from the inner window.
+    pub fn has_pending_events(&mut self) -> bool {
+        if self.paused {return false}
+        if self.peeked.is_some() {return true}
+        self.advance_animation(Instant::now());
+        if !self.suppress_synthetic {self.release_held_resize()}
+        if !self.buffered.is_empty() || self.paired.is_some() {return true}
+        if !self.suppress_synthetic &&
+           ((self.resize_pending && self.deferred_polls == 0 && self.throttle_remaining().is_none()) ||
+            !self.injected.is_empty()) {return true}
+        #[cfg(feature = "recording")]
+        {
+            if !self.suppress_synthetic && !self.replay.is_empty() {return true}
+        }
+        let e = self.poll_inner();
+        self.buffered.extend(e);
+        !self.buffered.is_empty()
+    }
+
+    /// Returns the next event that does not come from the inner window, if any.
+    ///
+    /// Paired scroll events and derived relative motion come first, right after their event,
+    /// then resize events generated by the wrapper, then replayed events.
+    /// Only the paired events are returned while synthetic events are suppressed.
+    fn pending_event(&mut self) -> Option<Event> {
+        if let Some(e) = self.paired.take() {return Some(e)}
+        if self.suppress_synthetic {return None}
+        if let Some(e) = self.synthetic_event() {return Some(e)}
+        #[cfg(feature = "recording")]
+        {
+            if let Some(e) = self.replay.pop_front() {return Some(e)}
+        }
+        None
+    }
+
+    /// Polls the next unmapped event from the injected events or the inner window.
+    ///
+    /// Injected events come first, then events buffered by `has_pending_events` or while paused.
+    fn poll_unmapped(&mut self) -> Option<Event> {
+        self.queued_unmapped().or_else(|| self.poll_inner())
+    }
+
+    /// Polls the inner window, holding back events for the input latency.
+    fn poll_inner(&mut self) -> Option<Event> {
+        if self.input_latency.is_zero() && self.delayed.is_empty() {return self.inner.poll_event()}
+        let now = Instant::now();
+        while let Some(e) = self.inner.poll_event() {self.delayed.push_back((now, e))}
+        self.release_delayed()
+    }
+
+    /// Waits for an event from the inner window, holding back events for the input latency.
+    ///
+    /// Returns `None` if the event is held back.
+    fn wait_inner(&mut self) -> Option<Event> {
+        if self.input_latency.is_zero() && self.delayed.is_empty() {return Some(self.inner.wait_event())}
+        let e = self.inner.wait_event();
+        self.delayed.push_back((Instant::now(), e));
+        self.release_delayed()
+    }
+
+    /// Waits for an event from the inner window with a timeout,
+    /// holding back events for the input latency.
+    fn wait_inner_timeout(&mut self, timeout: Duration) -> Option<Event> {
+        if self.input_latency.is_zero() && self.delayed.is_empty() {
+            return self.inner.wait_event_timeout(timeout)
+        }
+        if let Some(e) = self.inner.wait_event_timeout(timeout) {self.delayed.push_back((Instant::now(), e))}
+        self.release_delayed()
+    }
+
+    /// Returns the next unmapped event that is available without polling the inner window.
+    ///
+    /// This lets the waiting methods return injected and buffered events without blocking.
+    fn queued_unmapped(&mut self) -> Option<Event> {
+        if !self.suppress_synthetic {
+            if let Some(e) = self.injected.pop_front() {return Some(Event::Input(e, None))}
+        }
+        self.buffered.pop_front()
+    }
+
+    /// Handles an event before it is returned from a polling method.
+    fn deliver(&mut self, e: Event) -> Event {
+        let e = match (e, &mut self.hooks.timestamp_transform) {
+            (Event::Input(e, Some(ts)), Some(f)) => Event::Input(e, Some(f(ts))),
+            (e, _) => e,
+        };
+        #[cfg(feature = "recording")]
+        self.record(&e);
+        if let (Event::Input(e, ts), Some(sender)) = (&e, &self.hooks.event_channel) {
+            if sender.send((e.clone(), *ts)).is_err() {self.hooks.event_channel = None}
+        }
+        let synthetic = std::mem::take(&mut self.synthetic);
+        if let Event::Input(ref e, _) = e {
+            if let Input::Resize(_) = e {self.last_resize_synthetic = Some(synthetic)}
+            self.last_event = Some(e.clone());
+        }
+        e
+    }
+
+    /// Schedules a resize event with a draw size that deliberately violates the DPI factor.
+    ///
+    /// This is an abnormal test hook that simulates platforms where the framebuffer and
+    /// the window get out of sync briefly, e.g. during a live resize.
+    /// The offset is added to the draw size of the next resize event generated by the wrapper,
+    /// such that `draw_size != window_size * dpi`. Later resize events are normal again.
+    pub fn inject_divergent_resize(&mut self, offset: [i32; 2]) {
+        self.draw_size_offset = Some(offset);
+        self.resize_pending = true;
+    }
+
+    /// Returns the next event generated by the wrapper, if any.
+    fn synthetic_event(&mut self) -> Option<Event> {
+        self.advance_animation(Instant::now());
+        self.release_held_resize();
+        if self.resize_pending && self.deferred_polls > 0 {
+            self.deferred_polls -= 1;
+            return None
+        }
+        if self.resize_pending && self.throttle_remaining().is_none() {
+            self.resize_pending = false;
+            if !self.resize_throttle.is_zero() {self.last_resize = Some(Instant::now())}
+            let window_size = self.size();
+            let mut draw_size = to_pixels(self.draw_size());
+            if let Some(offset) = self.draw_size_offset.take() {
+                for i in 0..2 {draw_size[i] = draw_size[i].saturating_add_signed(offset[i])}
+            }
+            self.synthetic = true;
+            return Some(Event::Input(Input::Resize(ResizeArgs {
+                window_size: window_size.into(),
+                draw_size,
+            }), None));
+        }
+        None
+    }
+}
+
+/// The options of the window decide which coordinates are scaled, and how.
+impl<W> Scaling for FakeDpiWindow<W> {
+    fn cursor(&self, pos: [f64; 2]) -> Option<[f64; 2]> {
+        if self.cursor_space == CoordinateSpace::Physical {return None}
+        Some(match self.hooks.coordinate_mapper {
+            Some(ref mapper) => mapper.map_cursor(pos, self.input_scale()),
+            None => self.round(self.input_to_logical(pos)),
+        })
+    }
+    fn relative(&self, d: [f64; 2]) -> Option<[f64; 2]> {
+        if self.cursor_space == CoordinateSpace::Physical ||
+           !self.scale_relative_motion || self.cursor_captured {return None}
+        Some(match self.hooks.coordinate_mapper {
+            Some(ref mapper) => mapper.map_relative(d, self.input_scale()),
+            None => self.input_to_logical(d),
+        })
+    }
+    fn scroll(&self, d: [f64; 2]) -> Option<[f64; 2]> {
+        // Scroll deltas are usually ticks, which are only scaled on request.
+        if !self.scale_scroll {return None}
+        Some(match self.hooks.coordinate_mapper {
+            Some(ref mapper) => mapper.map_scroll(d, self.input_scale()),
+            None => self.round(self.input_to_logical(d)),
+        })
+    }
+    fn touch(&self, args: input::TouchArgs) -> Option<input::TouchArgs> {
+        self.hooks.coordinate_mapper.as_ref().map(|mapper| mapper.map_touch(args, self.input_scale()))
+    }
+    fn resize(&self, args: ResizeArgs) -> Option<ResizeArgs> {
+        // The draw size is computed from the logical window size,
+        // such that `draw_size == window_size * dpi` regardless of
+        // whether the inner window reports a physical or logical draw size,
+        // unless the inner window is a stacked layer.
+        if self.report_physical_size {
+            let window_size = self.orientation.size(args.window_size.into());
+            return Some(ResizeArgs {
+                window_size: window_size.into(),
+                draw_size: to_pixels(window_size),
+            })
+        }
+        let window_size = self.orientation.size(self.map_size(args.window_size.into()));
+        let inner = (args.window_size, args.draw_size.map(f64::from));
+        Some(ResizeArgs {
+            window_size: window_size.into(),
+            draw_size: to_pixels(self.draw_size_of(window_size, inner)),
+        })
+    }
+}
+
+impl<W: Default> Default for FakeDpiWindow<W> {
+    /// Wraps a default inner window with the default DPI factor `2.0`.
+    fn default() -> FakeDpiWindow<W> {FakeDpiWindow::new(W::default(), DEFAULT_DPI)}
+}
+
+impl<W: fmt::Debug> fmt::Debug for FakeDpiWindow<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FakeDpiWindow")
+            .field("inner", &self.inner)
+            .field("dpi_x", &self.dpi_x.get())
+            .field("dpi_y", &self.dpi_y.get())
+            .field("min_dpi", &self.min_dpi)
+            .field("max_dpi", &self.max_dpi)
+            .field("enabled", &self.enabled)
+            .field("rounding", &self.rounding)
+            .field("cursor_space", &self.cursor_space)
+            .field("scale_scroll", &self.scale_scroll)
+            .field("monitors", &self.monitors)
+            .field("active_monitor", &self.active_monitor)
+            .field("resize_pending", &self.resize_pending)
+            .field("animating", &self.animation.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Gives access to methods of the inner window.
+///
+/// Methods of `Window` and `AdvancedWindow` still go through the wrapper,
+/// because method resolution picks the wrapper's own implementation first.
+/// Inherent methods of the inner window bypass the DPI scaling.
+impl<W> Deref for FakeDpiWindow<W> {
+    type Target = W;
+    fn deref(&self) -> &W {&self.inner}
+}
+
+impl<W> DerefMut for FakeDpiWindow<W> {
+    fn deref_mut(&mut self) -> &mut W {&mut self.inner}
+}
+
+impl<W> AsRef<W> for FakeDpiWindow<W> {
+    fn as_ref(&self) -> &W {&self.inner}
+}
+
+impl<W> AsMut<W> for FakeDpiWindow<W> {
+    fn as_mut(&mut self) -> &mut W {&mut self.inner}
+}
+
+impl<W: BuildFromWindowSettings> BuildFromWindowSettings for FakeDpiWindow<W> {
+    fn build_from_window_settings(
+        settings: &WindowSettings
+    ) ->  Result<Self, Box<dyn Error + 'static>> {
+        FakeDpiWindowSettings::new(settings.clone()).build_window()
+    }
+}
+
+impl<W: Window> Window for FakeDpiWindow<W> {
+    fn set_should_close(&mut self, val: bool) {self.inner.set_should_close(val)}
+    fn should_close(&self) -> bool {
+        self.inner.should_close() || self.auto_close_after.is_some_and(|n| self.frames >= n)
+    }
+    /// Returns the logical window size.
+    ///
+    /// This is the physical size divided by the DPI factor, rounded with the rounding mode.
+    /// If sizes are reported in physical pixels, this is the physical size.
+    ///
+    /// # Panics
+    ///
+    /// In strict mode, if the logical size is not finite.
+    fn size(&self) -> Size {
+        if self.report_physical_size {return self.orientation.size(self.inner_size())}
+        self.logical_size()
+    }
+    fn swap_buffers(&mut self) {
+        if let Some(ref mut f) = self.hooks.frame_hook {f()}
+        self.frames = self.frames.saturating_add(1);
+        self.inner.swap_buffers();
+        if self.hooks.capture_hook.is_some() {
+            let (size, draw_size) = (self.size(), self.draw_size());
+            if let Some(ref mut f) = self.hooks.capture_hook {f(size, draw_size)}
+        }
+    }
+    fn wait_event(&mut self) -> Event {
+        if let Some((_, e)) = self.peeked.take() {return e}
+        loop {
+            self.detect_size_change();
+            if let Some(e) = self.pending_event() {return self.deliver(e)}
+            let e = match self.queued_unmapped() {
+                Some(e) => e,
+                None => match self.wake_up() {
+                    Some(timeout) => match self.wait_inner_timeout(timeout) {
+                        Some(e) => e,
+                        None => continue,
+                    },
+                    None => match self.wait_inner() {
+                        Some(e) => e,
+                        None => continue,
+                    },
+                }
+            };
+            if let Some(e) = self.process_event(e) {return self.deliver(e)}
+        }
+    }
+    fn wait_event_timeout(&mut self, val: Duration) -> Option<Event> {
+        if self.paused {
+            let e = self.wait_inner_timeout(val);
+            self.buffered.extend(e);
+            self.buffer_while_paused();
+            return None
+        }
+        if let Some((_, e)) = self.peeked.take() {return Some(e)}
+        self.detect_size_change();
+        if let Some(e) = self.pending_event() {return Some(self.deliver(e))}
+        let e = match self.queued_unmapped() {
+            Some(e) => Some(e),
+            None => {
+                let early = self.wake_up();
+                self.wait_inner_timeout(early.filter(|&timeout| timeout < val).unwrap_or(val))
+            }
+        };
+        // Without an event, a throttled resize event might be due now.
+        let e = e.and_then(|e| self.process_event(e)).or_else(|| self.pending_event());
+        e.map(|e| self.deliver(e))
+    }
+    fn poll_event(&mut self) -> Option<Event> {
+        if self.buffer_while_paused() {return None}
+        if let Some((_, e)) = self.peeked.take() {return Some(e)}
+        loop {
+            if let Some(e) = self.pending_event() {return Some(self.deliver(e))}
+            let e = match self.poll_unmapped() {
+                Some(e) => e,
+                None if self.detect_size_change() => continue,
+                None => return None,
+            };
+            if let Some(e) = self.process_event(e) {return Some(self.deliver(e))}
+        }
+    }
+    /// Returns the size of the simulated framebuffer.
+    ///
+    /// This is the logical window size multiplied by the DPI factor,
+    /// independent of what the inner window reports as its draw size.
+    /// Layers added with `stack` also multiply by the ratio of draw size to size of the layer below.
+    /// If sizes are reported in physical pixels, this is the physical size.
+    fn draw_size(&self) -> Size {
+        if self.report_physical_size {return self.size()}
+        if let Some((size, scale, _)) = self.letterbox_mapping() {
+            return Size {width: size.width * scale, height: size.height * scale}
+        }
+        let inner = (self.inner.size().into(), self.inner.draw_size().into());
+        self.draw_size_of(self.logical_size(), inner)
+    }
+}
+
+impl<W: AdvancedWindow> AdvancedWindow for FakeDpiWindow<W> {
+    fn get_title(&self) -> String {self.inner.get_title()}
+    fn set_title(&mut self, val: String) {
+        self.inner.set_title(val);
+        if let Some(f) = self.retag_title {f(&mut self.inner, self.dpi_x.get())}
+    }
+    fn get_exit_on_esc(&self) -> bool {self.inner.get_exit_on_esc()}
+    fn set_exit_on_esc(&mut self, val: bool) {self.inner.set_exit_on_esc(val)}
+    fn get_automatic_close(&self) -> bool {self.inner.get_automatic_close()}
+    fn set_automatic_close(&mut self, val: bool) {self.inner.set_automatic_close(val)}
+    fn set_capture_cursor(&mut self, val: bool) {
+        self.cursor_captured = val;
+        self.inner.set_capture_cursor(val)
+    }
+    fn show(&mut self) {
+        self.visible = true;
+        self.inner.show()
+    }
+    fn hide(&mut self) {
+        self.visible = false;
+        self.inner.hide()
+    }
+    /// Returns the position of the inner window, unscaled.
+    ///
+    /// The window position is in screen coordinates owned by the OS, not in the
+    /// window's own coordinate space, so it is not scaled by the DPI factor in either direction.
+    /// This keeps `set_position(get_position())` a no-op, which is not possible
+    /// when scaling integer positions by fractional DPI factors.
+    /// With a virtual desktop, the position is mapped on the monitor layout instead,
+    /// see `set_virtual_desktop`.
+    fn get_position(&self) -> Option<Position> {
+        self.inner.get_position().map(|pos| self.desktop_logical(pos))
+    }
+    /// Moves the inner window to a position, unscaled.
+    ///
+    /// Like `get_position`, the position is in screen coordinates and forwarded unchanged,
+    /// unless it is mapped on the monitor layout of a virtual desktop, see `set_virtual_desktop`.
+    fn set_position<P: Into<Position>>(&mut self, val: P) {
+        let pos = val.into();
+        let p = [pos.x as f64, pos.y as f64];
+        let monitor = if self.virtual_desktop {
+            self.monitor_layout.iter().position(|m| m.contains_logical(p))
+        } else {
+            None
+        };
+        let forwarded = match monitor {
+            Some(idx) => {
+                let p = self.monitor_layout[idx].physical_point(p);
+                Position {x: p[0].round() as i32, y: p[1].round() as i32}
+            }
+            None => pos,
+        };
+        self.inner.set_position(forwarded);
+        self.window_position = Some((pos, forwarded));
+        if let Some(idx) = monitor {
+            if self.active_monitor != Some(idx) {
+                let _ = self.set_active_monitor(idx);
+            }
+        }
+    }
+    // The physical size is rounded to whole pixels that map back to the same
+    // logical size, such that `set_size(size())` does not drift with fractional
+    // DPI factors. Without a rounding mode this is a no-op, and with a rounding
+    // mode it converges after the first call.
+    fn set_size<S: Into<Size>>(&mut self, val: S) {
+        let val = val.into();
+        if self.is_suspicious_size(val) {
+            let size = self.size();
+            if let Some(ref mut f) = self.hooks.suspicious_size_callback {f(val, size)}
+        }
+        let size = self.physical_size_for_logical(val);
+        if let Some(ref mut f) = self.hooks.set_size_hook {f(size)}
+        self.inner.set_size(size)
+    }
+}
+
+// OpenGL context operations do not involve coordinates.
+impl<W: OpenGLWindow> OpenGLWindow for FakeDpiWindow<W> {
+    fn get_proc_address(&mut self, proc_name: &str) -> ProcAddress {self.inner.get_proc_address(proc_name)}
+    fn is_current(&self) -> bool {self.inner.is_current()}
+    fn make_current(&mut self) {self.inner.make_current()}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::Motion;
+    use mock::MockWindow;
+    use std::sync::{Mutex, MutexGuard};
+
+    /// Must be held by tests that depend on the environment.
+    fn env_lock() -> MutexGuard<'static, ()> {
+        static LOCK: Mutex<()> = Mutex::new(());
+        LOCK.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn input(e: Input) -> Event {Event::Input(e, None)}
+
+    fn window(dpi_x: f64, dpi_y: f64) -> FakeDpiWindow<MockWindow> {
+        let mut w = FakeDpiWindow::new(MockWindow::new([600, 600]), 2.0);
+        w.set_dpi_xy(dpi_x, dpi_y).unwrap();
+        w.resize_pending = false;
+        w
+    }
+
+    #[test]
+    fn text_passthrough() {
+        let mut w = window(2.0, 2.0);
+        let e = Input::Text("é".to_string());
+        w.inner.push_input(e.clone());
+        assert_eq!(w.poll_event(), Some(input(Input::Text("é".to_string()))));
+        assert_eq!(map_input(2.0, e), Input::Text("é".to_string()));
+    }
+
+    #[test]
+    fn poll_inner_events() {
+        let mut w = window(2.0, 1.5);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([30.0, 30.0])));
+        w.inner.push_input(Input::Text("a".into()));
+        w.inner.push_input(Input::Move(Motion::MouseRelative([3.0, 3.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([15.0, 20.0])))));
+        assert_eq!(w.wait_event(), input(Input::Text("a".into())));
+        assert_eq!(w.wait_event_timeout(Duration::from_secs(0)),
+                   Some(input(Input::Move(Motion::MouseRelative([1.5, 2.0])))));
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn event_logger() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut w = window(2.0, 2.0);
+        let log = Rc::new(RefCell::new(vec![]));
+        let log2 = log.clone();
+        w.set_event_logger(Some(Box::new(move |raw: &Input, mapped: &Input| {
+            log2.borrow_mut().push((raw.clone(), mapped.clone()));
+        })));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([30.0, 10.0])));
+        w.set_dpi(1.0).unwrap();
+        w.poll_event();
+        w.set_dpi(2.0).unwrap();
+        w.poll_event();
+        w.poll_event();
+        assert_eq!(*log.borrow(), vec![(
+            Input::Move(Motion::MouseCursor([30.0, 10.0])),
+            Input::Move(Motion::MouseCursor([15.0, 5.0])),
+        )]);
+    }
+
+    #[test]
+    fn inject_event() {
+        let mut w = window(2.0, 2.0);
+        w.inner.push_input(Input::Focus(true));
+        w.inject_event(Input::Move(Motion::MouseCursor([30.0, 10.0])));
+        w.set_dpi(1.0).unwrap();
+        assert!(matches!(w.poll_event(), Some(Event::Input(Input::Resize(_), None))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([30.0, 10.0])))));
+        w.set_dpi(2.0).unwrap();
+        w.poll_event();
+        w.inject_event(Input::Move(Motion::MouseCursor([30.0, 10.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([15.0, 5.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(true))));
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn per_axis_motion() {
+        let mut w = window(2.0, 1.5);
+        assert_eq!(w.map_input(input(Input::Move(Motion::MouseCursor([30.0, 30.0])))),
+                   input(Input::Move(Motion::MouseCursor([15.0, 20.0]))));
+        assert_eq!(w.map_input(input(Input::Move(Motion::MouseRelative([3.0, 3.0])))),
+                   input(Input::Move(Motion::MouseRelative([1.5, 2.0]))));
+        w.set_scale_scroll(true);
+        assert_eq!(w.map_input(input(Input::Move(Motion::MouseScroll([6.0, 6.0])))),
+                   input(Input::Move(Motion::MouseScroll([3.0, 4.0]))));
+    }
+
+    #[test]
+    fn relative_motion_flag() {
+        let mut w = window(2.0, 2.0);
+        let rel = input(Input::Move(Motion::MouseRelative([4.0, -2.0])));
+        let cursor = input(Input::Move(Motion::MouseCursor([4.0, 2.0])));
+        assert!(w.get_scale_relative_motion());
+        assert_eq!(w.map_input(rel.clone()), input(Input::Move(Motion::MouseRelative([2.0, -1.0]))));
+        w.set_scale_relative_motion(false);
+        assert_eq!(w.map_input(rel.clone()), rel);
+        assert_eq!(w.map_input(cursor), input(Input::Move(Motion::MouseCursor([2.0, 1.0]))));
+    }
+
+    #[test]
+    fn scroll_is_unscaled() {
+        let w = window(3.0, 3.0);
+        let e = input(Input::Move(Motion::MouseScroll([1.0, -2.0])));
+        assert_eq!(w.map_input(e.clone()), e);
+    }
+
+    #[test]
+    fn touch_is_normalized() {
+        use input::{Touch, TouchArgs};
+
+        let e = input(Input::Move(Motion::Touch(TouchArgs::new(0, 1, [0.5, 0.25], 1.0, Touch::Move))));
+        assert_eq!(window(2.0, 2.0).map_input(e.clone()), e);
+    }
+
+    #[test]
+    fn touch_keeps_other_fields() {
+        use input::{Touch, TouchArgs};
+
+        let mut w = window(2.0, 2.0);
+        w.set_flip_y(true);
+        w.set_origin([30.0, 0.0]);
+        let args = TouchArgs::new_3d(7, 42, [0.5, 0.25, 0.75], [0.1, 0.2, 0.3], Touch::Start);
+        w.inner.push_input(Input::Move(Motion::Touch(args)));
+        let mapped = match w.poll_event() {
+            Some(Event::Input(Input::Move(Motion::Touch(mapped)), None)) => mapped,
+            e => panic!("unexpected event {:?}", e),
+        };
+        assert_eq!(mapped.position_3d, [0.4, 0.75, 0.75]);
+        assert_eq!((mapped.device, mapped.id), (7, 42));
+        assert_eq!(mapped.pressure_3d, [0.1, 0.2, 0.3]);
+        assert!(mapped.is_3d);
+        assert_eq!(mapped.touch, Touch::Start);
+    }
+
+    #[test]
+    fn file_drag_is_positionless() {
+        use input::FileDrag;
+        use std::path::PathBuf;
+
+        let w = window(2.0, 2.0);
+        for drag in [
+            FileDrag::Hover(PathBuf::from("a.png")),
+            FileDrag::Drop(PathBuf::from("a.png")),
+            FileDrag::Cancel,
+        ] {
+            let e = input(Input::FileDrag(drag));
+            assert_eq!(w.map_input(e.clone()), e);
+        }
+    }
+
+    #[test]
+    fn cursor_enter_is_positionless() {
+        let w = window(2.0, 2.0);
+        for entered in [true, false] {
+            let e = input(Input::Cursor(entered));
+            // The payload is only a flag. If it gains a position, this stops compiling,
+            // and `map_input` must scale the position.
+            if let Event::Input(Input::Cursor(flag), _) = w.map_input(e.clone()) {
+                let _: bool = flag;
+            }
+            assert_eq!(w.map_input(e.clone()), e);
+        }
+    }
+
+    #[test]
+    fn resize_draw_size_invariant() {
+        let w = window(2.0, 2.0);
+        let logical = input(Input::Resize(ResizeArgs {window_size: [600.0, 400.0], draw_size: [600, 400]}));
+        let physical = input(Input::Resize(ResizeArgs {window_size: [600.0, 400.0], draw_size: [1200, 800]}));
+        for e in [logical, physical] {
+            assert_eq!(w.map_input(e), input(Input::Resize(ResizeArgs {
+                window_size: [300.0, 200.0],
+                draw_size: [600, 400],
+            })));
+        }
+    }
+
+    #[test]
+    fn per_axis_resize() {
+        let e = input(Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [600, 600]}));
+        assert_eq!(window(2.0, 1.5).map_input(e),
+                   input(Input::Resize(ResizeArgs {window_size: [300.0, 400.0], draw_size: [600, 600]})));
+    }
+
+    #[test]
+    fn per_axis_size() {
+        let _lock = env_lock();
+        let mut w: FakeDpiWindow<MockWindow> = WindowSettings::new("test", [300, 300]).build().unwrap();
+        assert_eq!(w.inner.size(), Size {width: 600.0, height: 600.0});
+        w.set_dpi_xy(2.0, 1.5).unwrap();
+        assert_eq!(w.size(), Size {width: 300.0, height: 400.0});
+        w.set_dpi(3.0).unwrap();
+        assert_eq!([w.dpi_x(), w.dpi_y()], [3.0, 3.0]);
+        assert_eq!(w.size(), Size {width: 200.0, height: 200.0});
+    }
+
+    #[test]
+    fn build_with_fake_dpi() {
+        let w: FakeDpiWindow<MockWindow> = FakeDpiWindowSettings::new(WindowSettings::new("test", [300, 300]))
+            .fake_dpi(3.0)
+            .build()
+            .unwrap();
+        assert_eq!(w.inner.size(), Size {width: 900.0, height: 900.0});
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+    }
+
+    #[test]
+    fn build_size_modes() {
+        let settings = FakeDpiWindowSettings::new(WindowSettings::new("test", [800, 600])).fake_dpi(2.0);
+        assert_eq!(settings.get_size_mode(), BuildSizeMode::InflatePhysical);
+        let w: FakeDpiWindow<MockWindow> = settings.clone().build().unwrap();
+        assert_eq!(w.inner.size(), Size {width: 1600.0, height: 1200.0});
+        assert_eq!(w.size(), Size {width: 800.0, height: 600.0});
+        let w: FakeDpiWindow<MockWindow> = settings.size_mode(BuildSizeMode::KeepPhysical).build().unwrap();
+        assert_eq!(w.inner.size(), Size {width: 800.0, height: 600.0});
+        assert_eq!(w.size(), Size {width: 400.0, height: 300.0});
+    }
+
+    #[test]
+    fn build_with_env_dpi() {
+        let _lock = env_lock();
+        let settings = WindowSettings::new("test", [300, 300]);
+        std::env::set_var(DPI_ENV_VAR, "1.5");
+        let w: Result<FakeDpiWindow<MockWindow>, _> = settings.build();
+        std::env::set_var(DPI_ENV_VAR, "zero");
+        let invalid: Result<FakeDpiWindow<MockWindow>, _> = settings.build();
+        let explicit: Result<FakeDpiWindow<MockWindow>, _> = FakeDpiWindowSettings::new(settings.clone())
+            .fake_dpi(3.0)
+            .build();
+        std::env::remove_var(DPI_ENV_VAR);
+        let unset: FakeDpiWindow<MockWindow> = settings.build().unwrap();
+        let w = w.unwrap();
+        assert_eq!([w.dpi_x(), w.dpi_y()], [1.5, 1.5]);
+        assert_eq!(w.inner.size(), Size {width: 450.0, height: 450.0});
+        let err = invalid.err().unwrap();
+        assert_eq!(err.downcast_ref::<InvalidDpiEnvVar>(), Some(&InvalidDpiEnvVar("zero".into())));
+        assert_eq!(err.to_string(), "Invalid `FAKE_DPI` value `zero`, expected a positive finite number");
+        assert_eq!(explicit.unwrap().dpi(), 3.0);
+        assert_eq!(unset.dpi(), 2.0);
+    }
+
+    #[test]
+    fn reject_invalid_dpi() {
+        let mut w = FakeDpiWindow::new(MockWindow::new([300, 300]), 2.0);
+        for &dpi in &[0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(w.set_dpi(dpi).is_err());
+            assert!(w.set_dpi_xy(1.5, dpi).is_err());
+            assert_eq!([w.dpi_x(), w.dpi_y()], [2.0, 2.0]);
+            let res: Result<FakeDpiWindow<MockWindow>, _> = FakeDpiWindowSettings::new(WindowSettings::new("test", [300, 300]))
+                .fake_dpi(dpi)
+                .build();
+            assert!(res.is_err());
+        }
+        assert_eq!(w.set_dpi(-1.0), Err(InvalidDpi(-1.0)));
+    }
+
+    #[test]
+    fn clamp_dpi_to_range() {
+        let mut w = FakeDpiWindow::new(MockWindow::new([300, 300]), 2.0);
+        assert_eq!(w.dpi_range(), [0.25, 8.0]);
+        w.set_dpi(10.0).unwrap();
+        assert_eq!(w.dpi(), 8.0);
+        w.set_dpi_xy(0.1, 1.5).unwrap();
+        assert_eq!([w.dpi_x(), w.dpi_y()], [0.25, 1.5]);
+        w.set_dpi_range(1.0, 2.0).unwrap();
+        assert_eq!([w.dpi_x(), w.dpi_y()], [1.0, 1.5]);
+        w.set_dpi(3.0).unwrap();
+        assert_eq!(w.dpi(), 2.0);
+        assert!(w.set_dpi(-3.0).is_err());
+        assert_eq!(w.set_dpi_range(0.0, 2.0), Err(DpiError::Invalid(InvalidDpi(0.0))));
+        assert_eq!(w.set_dpi_range(3.0, 2.0), Err(DpiError::InvertedRange {min: 3.0, max: 2.0}));
+        assert_eq!(w.dpi_range(), [1.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_with_invalid_dpi() {
+        FakeDpiWindow::new(MockWindow::new([300, 300]), 0.0);
+    }
+
+    #[test]
+    fn resize_on_dpi_change() {
+        let mut w = FakeDpiWindow::new(MockWindow::new([600, 600]), 2.0);
+        assert_eq!(w.poll_event(), None);
+        w.set_dpi_xy(3.0, 1.5).unwrap();
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [200.0, 400.0],
+            draw_size: [600, 600],
+        }))));
+        assert_eq!(w.poll_event(), None);
+        w.set_dpi(1.5).unwrap();
+        w.set_dpi(2.0).unwrap();
+        assert_eq!(w.wait_event_timeout(Duration::from_secs(0)), Some(input(Input::Resize(ResizeArgs {
+            window_size: [300.0, 300.0],
+            draw_size: [600, 600],
+        }))));
+        assert_eq!(w.wait_event_timeout(Duration::from_secs(0)), None);
+    }
+
+    #[test]
+    fn dpi_change_callback() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let mut w = FakeDpiWindow::new(MockWindow::new([600, 600]), 2.0);
+        let dpi = Rc::new(Cell::new(0.0));
+        let calls = Rc::new(Cell::new(0));
+        let (dpi2, calls2) = (dpi.clone(), calls.clone());
+        w.set_dpi_change_callback(Some(Box::new(move |val| {
+            dpi2.set(val);
+            calls2.set(calls2.get() + 1);
+        })));
+        w.set_dpi(2.0).unwrap();
+        assert_eq!(calls.get(), 0);
+        w.set_dpi(1.5).unwrap();
+        assert_eq!((dpi.get(), calls.get()), (1.5, 1));
+        w.set_dpi(20.0).unwrap();
+        assert_eq!((dpi.get(), calls.get()), (8.0, 2));
+        w.set_dpi(9.0).unwrap();
+        assert_eq!(calls.get(), 2);
+        w.set_dpi_range(1.0, 4.0).unwrap();
+        assert_eq!((dpi.get(), calls.get()), (4.0, 3));
+    }
+
+    #[test]
+    fn animate_dpi() {
+        let mut w = FakeDpiWindow::new(MockWindow::new([600, 600]), 2.0);
+        w.animate_dpi(3.0, Duration::from_secs(10)).unwrap();
+        assert!(w.is_animating_dpi());
+        let start = w.animation.as_ref().unwrap().start;
+        w.advance_animation(start + Duration::from_secs(5));
+        assert_eq!(w.dpi(), 2.5);
+        assert_eq!(w.size(), Size {width: 240.0, height: 240.0});
+        assert!(w.poll_event().is_some());
+        w.advance_animation(start + Duration::from_secs(11));
+        assert!(!w.is_animating_dpi());
+        assert_eq!(w.size(), Size {width: 200.0, height: 200.0});
+
+        w.animate_dpi_with(1.0, Duration::from_secs(10), |t| t * t).unwrap();
+        let start = w.animation.as_ref().unwrap().start;
+        w.advance_animation(start + Duration::from_secs(5));
+        assert_eq!(w.dpi(), 2.5);
+        w.animate_dpi(1.0, Duration::from_secs(0)).unwrap();
+        assert_eq!(w.dpi(), 1.0);
+        assert!(!w.is_animating_dpi());
+    }
+
+    #[test]
+    fn set_size_round_trip() {
+        let mut w = window(2.0, 1.5);
+        let size = w.size();
+        w.set_size(size);
+        assert_eq!(w.inner.size(), Size {width: 600.0, height: 600.0});
+        w.set_size([100, 100]);
+        assert_eq!(w.inner.set_size_calls, vec![
+            Size {width: 600.0, height: 600.0},
+            Size {width: 200.0, height: 150.0},
+        ]);
+        assert_eq!(w.size(), Size {width: 100.0, height: 100.0});
+    }
+
+    #[test]
+    fn physical_size_for_logical() {
+        let mut w = window(1.5, 1.5);
+        let target = Size {width: 101.0, height: 80.0};
+        let modes = [(RoundingMode::Round, 152.0), (RoundingMode::Floor, 152.0), (RoundingMode::Ceil, 151.0)];
+        for (mode, width) in modes {
+            w.set_rounding(mode);
+            let physical = w.physical_size_for_logical(target);
+            assert_eq!(physical, Size {width, height: 120.0});
+            w.inner.size = physical;
+            assert_eq!(w.size(), target);
+        }
+    }
+
+    #[test]
+    fn set_size_round_trip_is_stable() {
+        let mut seed = 0x2545_f491_u64;
+        let mut next = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as f64 / (1u64 << 31) as f64
+        };
+        let modes = [RoundingMode::None, RoundingMode::Round, RoundingMode::Floor,
+                     RoundingMode::Ceil, RoundingMode::Truncate];
+        for i in 0..500 {
+            let physical = Size {width: (1.0 + next() * 4000.0).floor(), height: (1.0 + next() * 4000.0).floor()};
+            let mut w = FakeDpiWindow::new(MockWindow::new(physical), 0.25 + next() * 7.75);
+            w.set_rounding(modes[i % modes.len()]);
+            let size = w.size();
+            w.set_size(size);
+            if w.get_rounding() == RoundingMode::None {
+                assert_eq!(w.inner.size(), physical, "dpi {}", w.dpi());
+            }
+            let (size, physical) = (w.size(), w.inner.size());
+            w.set_size(size);
+            assert_eq!(w.size(), size, "dpi {}", w.dpi());
+            assert_eq!(w.inner.size(), physical, "dpi {}", w.dpi());
+        }
+    }
+
+    #[test]
+    fn point_round_trip() {
+        for &(dpi_x, dpi_y) in &[(2.0, 1.5), (1.25, 3.0), (0.3, 7.0)] {
+            let w = window(dpi_x, dpi_y);
+            for &p in &[[0.0, 0.0], [1.0, 1.0], [123.4, 567.8], [-3.3, 1e6]] {
+                let q = w.physical_to_logical(w.logical_to_physical(p));
+                assert!((q[0] - p[0]).abs() < 1e-9 && (q[1] - p[1]).abs() < 1e-9);
+            }
+        }
+        let w = window(2.0, 1.5);
+        assert_eq!(w.logical_to_physical([10.0, 10.0]), [20.0, 15.0]);
+        assert_eq!(w.physical_to_logical_size(Size {width: 20.0, height: 15.0}),
+                   Size {width: 10.0, height: 10.0});
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let mut w = window(1.25, 1.25);
+        w.inner.set_size([751, 751]);
+        let cursor = input(Input::Move(Motion::MouseCursor([101.0, -101.0])));
+        for &(mode, x, y, size) in &[
+            (RoundingMode::None, 101.0 * 0.8, -101.0 * 0.8, 751.0 * 0.8),
+            (RoundingMode::Round, 81.0, -81.0, 601.0),
+            (RoundingMode::Floor, 80.0, -81.0, 600.0),
+            (RoundingMode::Ceil, 81.0, -80.0, 601.0),
+            (RoundingMode::Truncate, 80.0, -80.0, 600.0),
+        ] {
+            w.set_rounding(mode);
+            assert_eq!(w.map_input(cursor.clone()), input(Input::Move(Motion::MouseCursor([x, y]))));
+            assert_eq!(w.size(), Size {width: size, height: size});
+        }
+    }
+
+    #[test]
+    fn snap_size_to_pixels() {
+        let mut w = window(1.5, 1.5);
+        w.set_rounding(RoundingMode::Round);
+        w.inner.set_size([1367, 767]);
+        assert_eq!(w.size(), Size {width: 911.0, height: 511.0});
+        let e = input(Input::Resize(ResizeArgs {window_size: [1367.0, 767.0], draw_size: [1367, 767]}));
+        assert_eq!(w.map_input(e), input(Input::Resize(ResizeArgs {
+            window_size: [911.0, 511.0],
+            draw_size: [1367, 767],
+        })));
+        w.set_dpi(1.25).unwrap();
+        w.set_dpi(1.5).unwrap();
+        match w.poll_event() {
+            Some(Event::Input(Input::Resize(args), _)) => assert_eq!(args.window_size, [911.0, 511.0]),
+            e => panic!("expected resize, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn from_config() {
+        let inner = MockWindow::new([600, 600]);
+        let w = FakeDpiWindow::from_config(inner, FakeDpiConfig {
+            dpi_x: 1.5,
+            dpi_y: 10.0,
+            min_dpi: 1.0,
+            max_dpi: 3.0,
+            rounding: RoundingMode::Round,
+            scale_scroll: true,
+        }).unwrap();
+        assert_eq!([w.dpi_x(), w.dpi_y()], [1.5, 3.0]);
+        assert_eq!(w.dpi_range(), [1.0, 3.0]);
+        assert_eq!(w.get_rounding(), RoundingMode::Round);
+        assert!(w.get_scale_scroll());
+        assert_eq!(w.inner.size(), Size {width: 600.0, height: 600.0});
+
+        for config in [
+            FakeDpiConfig {dpi_x: 0.0, ..FakeDpiConfig::new()},
+            FakeDpiConfig {max_dpi: f64::NAN, ..FakeDpiConfig::new()},
+            FakeDpiConfig {min_dpi: 4.0, max_dpi: 2.0, ..FakeDpiConfig::new()},
+        ] {
+            let inner = MockWindow::new([600, 600]);
+            assert!(FakeDpiWindow::from_config(inner, config).is_err());
+        }
+    }
+
+    #[test]
+    fn from_config_inverted_range() {
+        let config = FakeDpiConfig {min_dpi: 4.0, max_dpi: 2.0, ..FakeDpiConfig::new()};
+        let err = FakeDpiWindow::from_config(MockWindow::new([600, 600]), config).err().unwrap();
+        assert_eq!(err, DpiError::InvertedRange {min: 4.0, max: 2.0});
+        assert_eq!(err.to_string(), "Invalid DPI range `4..=2`, the minimum is greater than the maximum");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn from_config_file() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/scenario.toml");
+        let w = FakeDpiWindow::from_config_file(MockWindow::new([600, 600]), path).unwrap();
+        assert_eq!([w.dpi_x(), w.dpi_y()], [1.5, 1.25]);
+        assert_eq!(w.get_rounding(), RoundingMode::Floor);
+        assert_eq!(w.size(), Size {width: 400.0, height: 480.0});
+        assert!(matches!(
+            FakeDpiWindow::from_config_file(MockWindow::new([600, 600]), "scenario.ini"),
+            Err(ConfigFileError::UnknownFormat(_))
+        ));
+    }
+
+    #[test]
+    fn with_config() {
+        let w = FakeDpiWindow::with_config(MockWindow::new([600, 600]), FakeDpiConfig {
+            dpi_x: 5.0,
+            dpi_y: 1.5,
+            min_dpi: 1.0,
+            max_dpi: 4.0,
+            rounding: RoundingMode::Floor,
+            ..FakeDpiConfig::new()
+        });
+        assert_eq!([w.dpi_x(), w.dpi_y()], [4.0, 1.5]);
+        assert_eq!(w.dpi_range(), [1.0, 4.0]);
+        assert_eq!(w.get_rounding(), RoundingMode::Floor);
+        assert_eq!(w.size(), Size {width: 150.0, height: 400.0});
+        assert_eq!(w.inner.size(), Size {width: 600.0, height: 600.0});
+        assert!(!w.resize_pending);
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_invalid_config() {
+        FakeDpiWindow::with_config(MockWindow::new([600, 600]), FakeDpiConfig {min_dpi: 0.0, ..FakeDpiConfig::new()});
+    }
+
+    #[test]
+    fn deref_to_inner() {
+        struct Inner(u32);
+        impl Inner {
+            fn get(&self) -> u32 {self.0}
+            fn set(&mut self, val: u32) {self.0 = val}
+        }
+
+        let mut w = FakeDpiWindow::new(Inner(1), 2.0);
+        w.set(3);
+        assert_eq!(w.get(), 3);
+
+        let w = window(2.0, 2.0);
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(Window::size(&*w), Size {width: 600.0, height: 600.0});
+    }
+
+    #[test]
+    fn disable_simulation() {
+        let mut w = window(1.5, 1.5);
+        w.set_rounding(RoundingMode::Round);
+        w.set_scale_scroll(true);
+        w.set_enabled(false);
+        assert!(!w.get_enabled());
+        assert!(w.poll_event().is_some());
+        for e in [
+            Input::Move(Motion::MouseCursor([10.5, 20.25])),
+            Input::Move(Motion::MouseRelative([1.0, 1.0])),
+            Input::Move(Motion::MouseScroll([0.0, 3.0])),
+            Input::Resize(ResizeArgs {window_size: [601.0, 600.0], draw_size: [601, 600]}),
+        ] {
+            assert_eq!(w.map_input(input(e.clone())), input(e));
+        }
+        assert_eq!(w.size(), Size {width: 600.0, height: 600.0});
+        w.set_size([640, 480]);
+        assert_eq!(w.inner.size(), Size {width: 640.0, height: 480.0});
+        w.set_enabled(true);
+        assert_eq!(w.dpi(), 1.5);
+        assert_eq!(w.size(), Size {width: 427.0, height: 320.0});
+    }
+
+    #[test]
+    fn draw_size_is_size_times_dpi() {
+        let mut w = window(2.0, 2.0);
+        w.inner.draw_size = Size {width: 1200.0, height: 1200.0};
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.draw_size(), Size {width: 600.0, height: 600.0});
+        w.set_dpi_xy(1.5, 3.0).unwrap();
+        assert_eq!(w.draw_size(), Size {width: 600.0, height: 600.0});
+    }
+
+    #[test]
+    fn reciprocal_matches_division() {
+        for &dpi in &[0.25, 1.0, 1.25, 1.5, 2.0, 3.0, 7.3] {
+            let w = window(dpi, dpi);
+            assert_eq!(w.dpi(), dpi);
+            for &x in &[0.0, 1.0, 17.0, 123.456, 1920.0, 1e7] {
+                let p = w.physical_to_logical([x, -x]);
+                let tolerance = (x / dpi).abs() * f64::EPSILON;
+                assert!((p[0] - x / dpi).abs() <= tolerance);
+                assert!((p[1] + x / dpi).abs() <= tolerance);
+            }
+        }
+    }
+
+    #[test]
+    fn switch_monitors() {
+        let mut w = window(2.0, 2.0);
+        assert!(w.set_monitors(vec![1.0, -2.0]).is_err());
+        w.set_monitors(vec![1.0, 2.0]).unwrap();
+        assert_eq!(w.active_monitor(), None);
+        w.set_active_monitor(0).unwrap();
+        assert_eq!((w.dpi(), w.active_monitor()), (1.0, Some(0)));
+        assert_eq!(w.size(), Size {width: 600.0, height: 600.0});
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [600.0, 600.0],
+            draw_size: [600, 600],
+        }))));
+        w.set_active_monitor(1).unwrap();
+        assert_eq!(w.dpi(), 2.0);
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [300.0, 300.0],
+            draw_size: [600, 600],
+        }))));
+        assert_eq!(w.set_active_monitor(2), Err(InvalidMonitor(2)));
+        assert_eq!((w.dpi(), w.active_monitor()), (2.0, Some(1)));
+    }
+
+    #[test]
+    fn monitor_layout() {
+        let mut w = window(2.0, 2.0);
+        let layout = vec![
+            Monitor {dpi: 1.0, position: [0.0, 0.0], size: [300.0, 600.0]},
+            Monitor {dpi: 2.0, position: [300.0, 0.0], size: [300.0, 600.0]},
+        ];
+        assert!(w.set_monitor_layout(vec![Monitor {dpi: 0.0, ..layout[0]}]).is_err());
+        w.set_monitor_layout(layout).unwrap();
+        assert_eq!(w.monitors(), &[1.0, 2.0]);
+        assert_eq!(w.monitor_at([299.0, 10.0]), Some(0));
+        assert_eq!(w.monitor_at([300.0, 10.0]), Some(1));
+        assert_eq!(w.monitor_at([600.0, 10.0]), None);
+
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 10.0])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([200.0, 10.0])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([400.0, 10.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([100.0, 10.0])))));
+        assert_eq!(w.active_monitor(), Some(0));
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [600.0, 600.0],
+            draw_size: [600, 600],
+        }))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([200.0, 10.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([200.0, 5.0])))));
+        assert_eq!(w.active_monitor(), Some(1));
+        assert_eq!(w.dpi(), 2.0);
+        assert!(matches!(w.poll_event(), Some(Event::Input(Input::Resize(_), _))));
+        assert_eq!(w.poll_event(), None);
+
+        w.set_monitors(vec![1.0]).unwrap();
+        assert!(w.monitor_layout().is_empty());
+    }
+
+    #[test]
+    fn diagnostics() {
+        let mut w = window(1.5, 2.0);
+        w.set_flip_y(true);
+        let s = w.diagnostics();
+        assert!(s.starts_with("dpi: 1.5x2 (range 0.25..=8, effective [1.5, 2.0])\n"));
+        assert!(s.contains("\nlogical size: 400x300\n"));
+        assert!(s.contains("\ndraw size: 600x600\n"));
+        assert!(s.contains("flip y: true"));
+        assert!(s.contains("\nclamp cursor: false, epsilon: 1e-9\n"));
+        assert!(s.contains("\ncursor transform: CursorTransform {"));
+    }
+
+    #[test]
+    fn debug() {
+        let w = window(1.5, 2.0);
+        let s = format!("{:?}", w);
+        assert!(s.starts_with("FakeDpiWindow { inner: MockWindow {"));
+        assert!(s.contains("dpi_x: 1.5, dpi_y: 2.0"));
+    }
+
+    #[test]
+    fn clone() {
+        let mut w = window(1.5, 2.0);
+        w.set_rounding(RoundingMode::Floor);
+        w.set_dpi_change_callback(Some(Box::new(|_| {})));
+        w.inner.title = "test".into();
+        let mut w2 = w.clone();
+        assert_eq!([w2.dpi_x(), w2.dpi_y()], [1.5, 2.0]);
+        assert_eq!(w2.get_rounding(), RoundingMode::Floor);
+        assert_eq!(w2.inner.title, "test");
+        assert!(w.hooks.dpi_change_callback.is_some());
+        assert!(w2.hooks.dpi_change_callback.is_none());
+        w2.set_dpi(3.0).unwrap();
+        assert_eq!(w.dpi(), 1.5);
+    }
+
+    #[test]
+    fn initial_resize() {
+        let settings = FakeDpiWindowSettings::new(WindowSettings::new("test", [300, 200])).fake_dpi(1.5);
+        let mut w: FakeDpiWindow<MockWindow> = settings.build().unwrap();
+        assert_eq!(w.poll_event(), None);
+
+        let mut w: FakeDpiWindow<MockWindow> = settings.initial_resize(true).build().unwrap();
+        w.inner.push_input(Input::Focus(true));
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [300.0, 200.0],
+            draw_size: [450, 300],
+        }))));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(true))));
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn position_is_unscaled() {
+        let mut w = window(2.0, 2.0);
+        w.set_position([101, 51]);
+        assert_eq!(w.get_position(), Some(Position {x: 101, y: 51}));
+        let pos = w.get_position().unwrap();
+        w.set_position(pos);
+        assert_eq!(w.inner.set_position_calls, vec![Position {x: 101, y: 51}; 2]);
+    }
+
+    #[test]
+    fn virtual_desktop() {
+        let mut w = window(1.0, 1.0);
+        w.set_monitor_layout(vec![
+            Monitor {dpi: 1.0, position: [0.0, 0.0], size: [1920.0, 1080.0]},
+            Monitor {dpi: 2.0, position: [1920.0, 0.0], size: [2560.0, 1440.0]},
+        ]).unwrap();
+        w.set_position([2000, 100]);
+        assert_eq!(w.inner.set_position_calls, vec![Position {x: 2000, y: 100}]);
+        assert_eq!(w.active_monitor(), None);
+
+        w.set_virtual_desktop(true);
+        w.set_position([2000, 100]);
+        assert_eq!(w.inner.set_position_calls[1], Position {x: 2080, y: 200});
+        assert_eq!(w.get_position(), Some(Position {x: 2000, y: 100}));
+        assert_eq!((w.dpi(), w.active_monitor()), (2.0, Some(1)));
+        w.set_position([100, 50]);
+        assert_eq!(w.inner.set_position_calls[2], Position {x: 100, y: 50});
+        assert_eq!((w.dpi(), w.active_monitor()), (1.0, Some(0)));
+        w.set_position([3500, 100]);
+        assert_eq!(w.inner.set_position_calls[3], Position {x: 3500, y: 100});
+        assert_eq!(w.get_position(), Some(Position {x: 3500, y: 100}));
+        // Moved by someone else, the physical position is mapped back.
+        w.inner.set_position([3520, 100]);
+        assert_eq!(w.get_position(), Some(Position {x: 2720, y: 50}));
+
+        for &p in &[[0, 0], [1919, 1079], [1920, 0], [2001, 99], [3199, 719], [3500, 100], [-50, 2000]] {
+            w.set_position(p);
+            assert_eq!(w.get_position(), Some(p.into()));
+            let pos = w.get_position().unwrap();
+            w.set_position(pos);
+            assert_eq!(w.get_position(), Some(p.into()));
+        }
+    }
+
+    #[test]
+    fn monitor_layout_on_desktop() {
+        let mut w = window(1.0, 1.0);
+        w.set_monitor_layout(vec![
+            Monitor {dpi: 1.0, position: [0.0, 0.0], size: [1920.0, 1080.0]},
+            Monitor {dpi: 2.0, position: [1920.0, 0.0], size: [2560.0, 1440.0]},
+        ]).unwrap();
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 10.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([100.0, 10.0])))));
+        assert_eq!(w.active_monitor(), Some(0));
+
+        // Cursor positions are relative to the window, which now starts on the second monitor.
+        w.set_position([1900, 0]);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 10.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.0, 5.0])))));
+        assert_eq!((w.dpi(), w.active_monitor()), (2.0, Some(1)));
+        assert!(matches!(w.poll_event(), Some(Event::Input(Input::Resize(_), _))));
+    }
+
+    #[test]
+    fn logical_and_physical_size() {
+        for &dpi in &[1.5, 2.0, 4.0] {
+            let w = window(dpi, dpi);
+            assert_eq!(w.logical_size(), w.size());
+            assert_eq!(w.physical_size(), Size {width: 600.0, height: 600.0});
+            let logical = w.logical_size();
+            assert_eq!(w.physical_size(), Size {width: logical.width * dpi, height: logical.height * dpi});
+        }
+    }
+
+    #[test]
+    fn as_ref_inner() {
+        fn title(w: impl AsRef<MockWindow>) -> String {w.as_ref().title.clone()}
+        fn set_title(mut w: impl AsMut<MockWindow>) {w.as_mut().title = "changed".into()}
+
+        let mut w = window(2.0, 2.0);
+        set_title(&mut w);
+        assert_eq!(title(&w), "changed");
+    }
+
+    #[test]
+    fn into_inner() {
+        let settings = FakeDpiWindowSettings::new(WindowSettings::new("test", [300, 200])).fake_dpi(2.0);
+        let w: FakeDpiWindow<MockWindow> = settings.build().unwrap();
+        let inner = w.into_inner();
+        assert_eq!(inner.size, Size {width: 600.0, height: 400.0});
+        assert_eq!(inner.title, "test");
+    }
+
+    #[test]
+    fn cycle_dpi_presets() {
+        let mut w = window(2.0, 2.0);
+        let mut seq = vec![];
+        for _ in 0..6 {
+            w.cycle_dpi_preset();
+            seq.push(w.dpi());
+        }
+        assert_eq!(seq, vec![3.0, 1.0, 1.25, 1.5, 2.0, 3.0]);
+        assert!(w.poll_event().is_some());
+
+        w.set_dpi_presets(vec![1.0, 4.0]).unwrap();
+        w.cycle_dpi_preset();
+        assert_eq!(w.dpi(), 4.0);
+        w.cycle_dpi_preset();
+        assert_eq!(w.dpi(), 1.0);
+        assert!(w.set_dpi_presets(vec![0.0]).is_err());
+        w.set_dpi_presets(vec![]).unwrap();
+        w.cycle_dpi_preset();
+        assert_eq!(w.dpi(), 1.0);
+
+        // A preset outside the DPI range is clamped, and the cycle still advances.
+        w.set_dpi_presets(vec![1.0, 10.0, 2.0]).unwrap();
+        let mut seq = vec![];
+        for _ in 0..4 {
+            w.cycle_dpi_preset();
+            seq.push(w.dpi());
+        }
+        assert_eq!(seq, vec![8.0, 2.0, 1.0, 8.0]);
+        w.set_dpi(1.5).unwrap();
+        w.cycle_dpi_preset();
+        assert_eq!(w.dpi(), 8.0);
+    }
+
+    #[test]
+    fn retag_title() {
+        let mut w = window(2.0, 2.0);
+        w.inner.title = "test".into();
+        w.set_tag_title(true);
+        assert!(w.get_tag_title());
+        assert_eq!(w.get_title(), "test (fake dpi 2x)");
+        w.set_dpi(1.5).unwrap();
+        assert_eq!(w.get_title(), "test (fake dpi 1.5x)");
+        w.set_dpi(3.0).unwrap();
+        assert_eq!(w.get_title(), "test (fake dpi 3x)");
+        w.set_title("other".into());
+        assert_eq!(w.get_title(), "other (fake dpi 3x)");
+        w.inner.title = "inner".into();
+        w.set_dpi(2.0).unwrap();
+        assert_eq!(w.get_title(), "inner (fake dpi 2x)");
+        w.set_tag_title(false);
+        assert_eq!(w.get_title(), "inner");
+        w.set_dpi(1.0).unwrap();
+        assert_eq!(w.get_title(), "inner");
+    }
+
+    #[test]
+    fn tag_title() {
+        let settings = FakeDpiWindowSettings::new(WindowSettings::new("test", [300, 200])).fake_dpi(1.5);
+        let w: FakeDpiWindow<MockWindow> = settings.clone().build().unwrap();
+        assert_eq!(w.get_title(), "test");
+        let w: FakeDpiWindow<MockWindow> = settings.tag_title(true).build().unwrap();
+        assert_eq!(w.get_title(), "test (fake dpi 1.5x)");
+    }
+
+    #[test]
+    fn scale_factor_tracks_dpi() {
+        let mut w = window(2.0, 2.0);
+        assert_eq!(w.scale_factor(), 2.0);
+        w.set_dpi(1.5).unwrap();
+        assert_eq!(w.scale_factor(), 1.5);
+        w.set_dpi_xy(1.25, 3.0).unwrap();
+        assert_eq!(w.scale_factors(), [1.25, 3.0]);
+        w.set_enabled(false);
+        assert_eq!(w.scale_factors(), [1.0, 1.0]);
+    }
+
+    #[test]
+    fn size_mismatch() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let mut w = window(2.0, 2.0);
+        assert!(!w.get_check_size_ratio());
+        let count = Rc::new(Cell::new(0));
+        let c = count.clone();
+        w.set_size_mismatch_callback(Some(Box::new(move |_| c.set(c.get() + 1))));
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [1200, 1200]}));
+        assert!(w.poll_event().is_some());
+        assert_eq!(count.get(), 0);
+
+        w.set_check_size_ratio(true);
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [600, 601]}));
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [1200, 1200]}));
+        while w.poll_event().is_some() {}
+        assert_eq!(count.get(), 1);
+
+        // A backend that already scales reports twice the draw size after `set_size`.
+        w.set_size([200.0, 100.0]);
+        let size = w.inner.size();
+        w.inner.push_input(Input::Resize(ResizeArgs {
+            window_size: size.into(),
+            draw_size: [size.width as u32 * 2, size.height as u32 * 2],
+        }));
+        while w.poll_event().is_some() {}
+        assert_eq!(count.get(), 2);
+
+        w.set_check_size_ratio(false);
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [1200, 1200]}));
+        while w.poll_event().is_some() {}
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn cursor_space() {
+        let mut w = window(2.0, 2.0);
+        assert_eq!(w.get_cursor_space(), CoordinateSpace::Logical);
+        let e = input(Input::Move(Motion::MouseCursor([500.0, 300.0])));
+        assert_eq!(w.map_input(e.clone()), input(Input::Move(Motion::MouseCursor([250.0, 150.0]))));
+
+        w.set_cursor_space(CoordinateSpace::Physical);
+        assert_eq!(w.map_input(e.clone()), e);
+        let e = input(Input::Move(Motion::MouseRelative([4.0, 2.0])));
+        assert_eq!(w.map_input(e.clone()), e);
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.draw_size(), Size {width: 600.0, height: 600.0});
+    }
+
+    #[test]
+    fn f32_conversions() {
+        let w = window(1.5, 3.0);
+        for &p in &[[0.0, 0.0], [100.0, 7.0], [333.25, 1e3], [-12.5, 0.1]] {
+            let p32 = [p[0] as f32, p[1] as f32];
+            let p64 = [p32[0] as f64, p32[1] as f64];
+            let expected = w.physical_to_logical(p64);
+            assert_eq!(w.physical_to_logical_f32(p32), [expected[0] as f32, expected[1] as f32]);
+            let expected = w.logical_to_physical(p64);
+            assert_eq!(w.logical_to_physical_f32(p32), [expected[0] as f32, expected[1] as f32]);
+        }
+        assert_eq!(w.physical_to_logical_f32([300.0, 300.0]), [200.0, 100.0]);
+    }
+
+    #[test]
+    fn event_transform() {
+        let mut w = window(2.0, 2.0);
+        let width = w.size().width;
+        w.set_event_transform(Some(Box::new(move |e| match e {
+            Input::Move(Motion::MouseCursor([x, y])) => Input::Move(Motion::MouseCursor([x.max(0.0).min(width), y])),
+            e => e,
+        })));
+        w.inject_event(Input::Move(Motion::MouseCursor([-20.0, 10.0])));
+        w.inject_event(Input::Move(Motion::MouseCursor([800.0, 10.0])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([200.0, 10.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([0.0, 5.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([300.0, 5.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([100.0, 5.0])))));
+    }
+
+    #[test]
+    fn clamp_cursor() {
+        let mut w = window(2.0, 2.0);
+        assert!(!w.get_clamp_cursor());
+        w.set_clamp_cursor(true);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([700.0, -10.0])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([200.0, 1000.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([300.0, 0.0])))));
+        w.set_cursor_space(CoordinateSpace::Physical);
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([200.0, 600.0])))));
+    }
+
+    #[test]
+    fn poll_event_raw() {
+        let mut w = window(2.0, 2.0);
+        w.inner.events.push_back(Event::Input(Input::Move(Motion::MouseCursor([40.0, 20.0])), Some(7)));
+        assert_eq!(w.poll_event_raw(), Some((
+            Input::Move(Motion::MouseCursor([40.0, 20.0])),
+            Input::Move(Motion::MouseCursor([20.0, 10.0])),
+            Some(7),
+        )));
+        w.set_dpi(3.0).unwrap();
+        let resize = Input::Resize(ResizeArgs {window_size: [200.0, 200.0], draw_size: [600, 600]});
+        assert_eq!(w.poll_event_raw(), Some((resize.clone(), resize, None)));
+        assert_eq!(w.poll_event_raw(), None);
+    }
+
+    #[test]
+    fn peek_event() {
+        let mut w = window(2.0, 2.0);
+        w.inner.events.push_back(Event::Input(Input::Move(Motion::MouseCursor([100.0, 50.0])), Some(3)));
+        let e = (Input::Move(Motion::MouseCursor([50.0, 25.0])), Some(3));
+        assert_eq!(w.peek_event(), Some(e.clone()));
+        assert_eq!(w.peek_event(), Some(e.clone()));
+        assert!(w.inner.events.is_empty());
+        assert!(w.has_pending_events());
+        assert_eq!(w.poll_event(), Some(Event::Input(e.0, e.1)));
+        assert_eq!(w.peek_event(), None);
+        assert_eq!(w.poll_event(), None);
+
+        w.set_dpi(3.0).unwrap();
+        w.inject_event(Input::Move(Motion::MouseCursor([30.0, 30.0])));
+        let resize = Input::Resize(ResizeArgs {window_size: [200.0, 200.0], draw_size: [600, 600]});
+        assert_eq!(w.peek_event(), Some((resize.clone(), None)));
+        assert_eq!(w.poll_event(), Some(Event::Input(resize, None)));
+        assert_eq!(w.peek_event(), Some((Input::Move(Motion::MouseCursor([10.0, 10.0])), None)));
+        assert_eq!(w.poll_event(), Some(Event::Input(Input::Move(Motion::MouseCursor([10.0, 10.0])), None)));
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn peek_then_poll_raw() {
+        use input::AfterRenderArgs;
+
+        let mut w = window(2.0, 2.0);
+        w.inner.events.push_back(Event::Input(Input::Move(Motion::MouseCursor([100.0, 50.0])), Some(3)));
+        assert_eq!(w.peek_event(), Some((Input::Move(Motion::MouseCursor([50.0, 25.0])), Some(3))));
+        assert_eq!(w.poll_event_raw(), Some((
+            Input::Move(Motion::MouseCursor([100.0, 50.0])),
+            Input::Move(Motion::MouseCursor([50.0, 25.0])),
+            Some(3),
+        )));
+
+        // Events that are not input events stay in the look-ahead slot.
+        let after_render = Event::Loop(AfterRenderArgs.into());
+        w.inner.events.push_back(after_render.clone());
+        assert_eq!(w.peek_event(), None);
+        assert_eq!(w.poll_event_raw(), None);
+        assert_eq!(w.poll_event(), Some(after_render));
+
+        // Delivery is paused for peeked events too, except with `wait_event`.
+        w.inner.push_input(Input::Focus(true));
+        assert_eq!(w.peek_event(), Some((Input::Focus(true), None)));
+        w.set_paused(true);
+        assert!(!w.has_pending_events());
+        assert_eq!(w.poll_event(), None);
+        assert_eq!(w.poll_event_raw(), None);
+        assert_eq!(w.wait_event_timeout(Duration::from_millis(1)), None);
+        assert_eq!(w.peek_event(), Some((Input::Focus(true), None)));
+        w.set_paused(false);
+        assert_eq!(w.wait_event_timeout(Duration::from_millis(1)), Some(input(Input::Focus(true))));
+    }
+
+    #[test]
+    fn timestamp_transform() {
+        let mut w = window(2.0, 2.0);
+        w.set_timestamp_transform(Some(Box::new(|ts| ts + 100)));
+        w.inner.events.push_back(Event::Input(Input::Focus(true), Some(5)));
+        w.inner.events.push_back(Event::Input(Input::Focus(false), None));
+        assert_eq!(w.poll_event(), Some(Event::Input(Input::Focus(true), Some(105))));
+        assert_eq!(w.poll_event(), Some(Event::Input(Input::Focus(false), None)));
+    }
+
+    #[test]
+    fn controller_axis_unscaled() {
+        use input::ControllerAxisArgs;
+
+        let mut w = window(3.0, 3.0);
+        let e = Input::Move(Motion::ControllerAxis(ControllerAxisArgs {id: 0, axis: 1, position: 0.75}));
+        w.inner.push_input(e.clone());
+        assert_eq!(w.poll_event(), Some(input(e)));
+    }
+
+    #[test]
+    fn poll_generic_event() {
+        use input::MouseCursorEvent;
+
+        let mut w = window(2.0, 2.0);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([50.0, 30.0])));
+        let e: Event = w.poll_generic_event().unwrap();
+        assert_eq!(e.mouse_cursor_args(), Some([25.0, 15.0]));
+        assert!(w.poll_generic_event::<Event>().is_none());
+    }
+
+    #[test]
+    fn resize_throttle() {
+        let resize = |size| Some(input(Input::Resize(ResizeArgs {window_size: [size; 2], draw_size: [600; 2]})));
+        let mut w = window(2.0, 2.0);
+        w.set_resize_throttle(Duration::from_secs(60));
+        w.set_dpi(3.0).unwrap();
+        assert_eq!(w.poll_event(), resize(200.0));
+        for &dpi in &[1.0, 1.5, 4.0] {
+            w.set_dpi(dpi).unwrap();
+            assert_eq!(w.poll_event(), None);
+        }
+        w.last_resize = w.last_resize.map(|t| t - Duration::from_secs(60));
+        assert_eq!(w.poll_event(), resize(150.0));
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn resize_debounce() {
+        let resize = |size| Input::Resize(ResizeArgs {window_size: [size; 2], draw_size: [size as u32; 2]});
+        let mut w = window(2.0, 2.0);
+        w.set_resize_debounce(Duration::from_secs(60));
+        for &size in &[700.0, 800.0, 900.0] {
+            w.inner.size = Size {width: size, height: size};
+            w.inner.push_input(resize(size));
+        }
+        w.inner.push_input(Input::Focus(true));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(true))));
+        assert_eq!(w.poll_event(), None);
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.held_resize.map(|(_, args)| args.window_size), Some([900.0; 2]));
+
+        w.held_resize = w.held_resize.map(|(t, args)| (t - Duration::from_secs(60), args));
+        assert!(w.has_pending_events());
+        assert_eq!(w.size(), Size {width: 450.0, height: 450.0});
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [450.0; 2],
+            draw_size: [900; 2],
+        }))));
+        assert_eq!(w.poll_event(), None);
+
+        w.inner.size = Size {width: 600.0, height: 600.0};
+        w.inner.push_input(resize(600.0));
+        assert_eq!(w.poll_event(), None);
+        w.set_resize_debounce(Duration::ZERO);
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [300.0; 2],
+            draw_size: [600; 2],
+        }))));
+    }
+
+    #[test]
+    fn contains_physical() {
+        let mut w = window(2.0, 2.0);
+        assert!(w.contains_physical([0.0, 0.0]));
+        assert!(w.contains_physical([599.0, 599.0]));
+        assert!(w.contains_physical([600.0, 300.0]));
+        w.set_epsilon(0.0);
+        assert!(!w.contains_physical([600.0, 300.0]));
+        assert!(!w.contains_physical([300.0, 600.0]));
+        assert!(!w.contains_physical([-0.5, 300.0]));
+        assert!(!w.contains_physical([300.0, 600.5]));
+        w.set_rounding(RoundingMode::Floor);
+        assert!(w.contains_physical([599.9, 599.9]));
+    }
+
+    #[test]
+    fn epsilon() {
+        let mut w = window(3.0, 3.0);
+        let edge = w.unmap_cursor_logical([200.0, 0.0]);
+        assert_eq!(w.epsilon(), 1e-9);
+        assert!(w.contains_physical(edge));
+        w.set_epsilon(0.0);
+        assert!(!w.contains_physical(edge));
+        w.set_epsilon(1e-9);
+        assert!(w.contains_physical([599.999_999, -1e-9]));
+        assert!(!w.contains_physical([600.1, 0.0]));
+        w.set_clamp_cursor(true);
+        let near = w.unmap_cursor_logical([199.999_999_999_9, 1e-12]);
+        assert_eq!(w.map_cursor_physical(near), [200.0, 0.0]);
+        assert_eq!(w.current_transform().apply(near), [200.0, 0.0]);
+        w.set_epsilon(-1.0);
+        assert_eq!(w.epsilon(), 0.0);
+    }
+
+    #[test]
+    fn capture_hook() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut w = window(1.5, 1.5);
+        let captures = Rc::new(RefCell::new(vec![]));
+        let c = captures.clone();
+        w.set_capture_hook(Some(Box::new(move |size, draw_size| c.borrow_mut().push((size, draw_size)))));
+        w.swap_buffers();
+        w.inner.size = Size {width: 300.0, height: 150.0};
+        w.inner.draw_size = w.inner.size;
+        w.swap_buffers();
+        assert_eq!(*captures.borrow(), vec![
+            (Size {width: 400.0, height: 400.0}, Size {width: 600.0, height: 600.0}),
+            (Size {width: 200.0, height: 100.0}, Size {width: 300.0, height: 150.0}),
+        ]);
+        assert_eq!(w.inner.swap_buffers_calls, 2);
+    }
+
+    #[test]
+    fn frame_hook() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let mut w = window(2.0, 2.0);
+        w.swap_buffers();
+        let frames = Rc::new(Cell::new(0));
+        let f = frames.clone();
+        w.set_frame_hook(Some(Box::new(move || f.set(f.get() + 1))));
+        w.swap_buffers();
+        w.swap_buffers();
+        assert_eq!(frames.get(), 2);
+        assert_eq!(w.inner.swap_buffers_calls, 3);
+    }
+
+    #[test]
+    fn opengl_window() {
+        fn make_current<W: OpenGLWindow>(w: &mut W) {w.make_current()}
+
+        let mut w = window(2.0, 2.0);
+        assert!(!w.is_current());
+        make_current(&mut w);
+        assert!(w.inner.current);
+        assert!(w.get_proc_address("glClear").is_null());
+    }
+
+    #[test]
+    fn captured_relative_motion() {
+        let mut w = window(2.0, 2.0);
+        w.inner.push_input(Input::Move(Motion::MouseRelative([10.0, -4.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseRelative([5.0, -2.0])))));
+        w.set_capture_cursor(true);
+        assert!(w.inner.capture_cursor);
+        w.inner.push_input(Input::Move(Motion::MouseRelative([10.0, -4.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseRelative([10.0, -4.0])))));
+        w.set_capture_cursor(false);
+        w.inner.push_input(Input::Move(Motion::MouseRelative([10.0, -4.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseRelative([5.0, -2.0])))));
+    }
+
+    #[test]
+    fn input_dpi() {
+        let mut w = window(2.0, 2.0);
+        assert_eq!(w.input_dpi(), 2.0);
+        w.set_input_dpi(Some(4.0)).unwrap();
+        assert_eq!(w.size_dpi(), 2.0);
+        assert_eq!(w.input_dpi(), 4.0);
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        w.inner.push_input(Input::Move(Motion::MouseCursor([400.0, 200.0])));
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [800.0, 600.0], draw_size: [800, 600]}));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([100.0, 50.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [400.0, 300.0],
+            draw_size: [800, 600],
+        }))));
+        assert_eq!(w.set_input_dpi(Some(-1.0)), Err(InvalidDpi(-1.0)));
+        w.set_input_dpi(None).unwrap();
+        assert_eq!(w.input_dpi(), 2.0);
+    }
+
+    #[test]
+    fn free_map_input() {
+        use std::path::PathBuf;
+        use input::{Button, ButtonArgs, ButtonState, CloseArgs, ControllerAxisArgs, FileDrag, Key, Touch, TouchArgs};
+
+        let w = window(2.0, 2.0);
+        let events = vec![
+            Input::Button(ButtonArgs {state: ButtonState::Press, button: Button::Keyboard(Key::A), scancode: None}),
+            Input::Move(Motion::MouseCursor([30.0, 11.0])),
+            Input::Move(Motion::MouseRelative([-4.0, 3.0])),
+            Input::Move(Motion::MouseScroll([0.0, 1.0])),
+            Input::Move(Motion::ControllerAxis(ControllerAxisArgs {id: 0, axis: 0, position: 0.5})),
+            Input::Move(Motion::Touch(TouchArgs::new(0, 0, [0.5, 0.5], 1.0, Touch::Start))),
+            Input::Text("a".into()),
+            Input::Resize(ResizeArgs {window_size: [601.0, 300.0], draw_size: [601, 300]}),
+            Input::Focus(true),
+            Input::Cursor(false),
+            Input::FileDrag(FileDrag::Drop(PathBuf::from("a.png"))),
+            Input::Close(CloseArgs),
+        ];
+        for e in events {
+            assert_eq!(input(map_input(2.0, e.clone())), w.map_input(input(e)));
+        }
+        assert_eq!(map_input(4.0, Input::Move(Motion::MouseCursor([8.0, 2.0]))),
+                   Input::Move(Motion::MouseCursor([2.0, 0.5])));
+    }
+
+    #[test]
+    fn size_at_dpi() {
+        let mut w = FakeDpiWindow::new(MockWindow::new([1368, 1026]), 2.0);
+        assert_eq!(w.size_at_dpi(w.dpi()), w.size());
+        assert_eq!(w.size_at_dpi(1.5), Size {width: 912.0, height: 684.0});
+        w.set_rounding(RoundingMode::Floor);
+        assert_eq!(w.size_at_dpi(4.0), Size {width: 342.0, height: 256.0});
+        assert_eq!(w.size_at_dpi(100.0), w.size_at_dpi(8.0));
+        assert_eq!(w.dpi(), 2.0);
+        w.set_dpi(1.5).unwrap();
+        assert_eq!(w.size_at_dpi(1.5), w.size());
+    }
+
+    #[test]
+    fn stacked_layers() {
+        let mut w = FakeDpiWindow::new(MockWindow::new([600, 600]), 1.5).stack(2.0);
+        assert!(!w.get_check_size_ratio());
+        assert_eq!(w.size(), Size {width: 200.0, height: 200.0});
+        assert_eq!(w.draw_size(), Size {width: 600.0, height: 600.0});
+        assert_eq!(super::dpi_for(w.draw_size(), w.size()), [3.0, 3.0]);
+
+        w.inner.inner.push_input(Input::Move(Motion::MouseCursor([90.0, 30.0])));
+        w.inner.inner.push_input(Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [600, 600]}));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([30.0, 10.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [200.0, 200.0],
+            draw_size: [600, 600],
+        }))));
+
+        w.set_size([100, 100]);
+        assert_eq!(w.inner.inner.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.size(), Size {width: 100.0, height: 100.0});
+        assert_eq!(w.draw_size(), Size {width: 300.0, height: 300.0});
+
+        let w = FakeDpiWindow::new(FakeDpiWindow::new(MockWindow::new([600, 600]), 1.5), 2.0);
+        assert_eq!(w.draw_size(), Size {width: 400.0, height: 400.0});
+    }
+
+    #[test]
+    fn reset_config() {
+        let mut w = window(3.0, 1.5);
+        w.set_rounding(RoundingMode::Floor);
+        w.set_clamp_cursor(true);
+        w.set_scale_scroll(true);
+        w.set_dpi_range(1.0, 4.0).unwrap();
+        w.set_dpi_presets(vec![1.0]).unwrap();
+        w.set_event_transform(Some(Box::new(|_| Input::Focus(false))));
+        w.inject_event(Input::Focus(true));
+        w.reset_config();
+        assert_eq!([w.dpi_x(), w.dpi_y()], [2.0, 2.0]);
+        assert_eq!(w.dpi_range(), [0.25, 8.0]);
+        assert_eq!(w.get_rounding(), RoundingMode::None);
+        assert!(!w.get_clamp_cursor());
+        assert!(!w.get_scale_scroll());
+        assert_eq!(w.dpi_presets(), &[1.0, 1.25, 1.5, 2.0, 3.0]);
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [300.0, 300.0],
+            draw_size: [600, 600],
+        }))));
+        w.inner.push_input(Input::Focus(true));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(true))));
+        assert_eq!(w.poll_event(), None);
+
+        w.reset_config();
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn flip_y() {
+        use input::{Touch, TouchArgs};
+
+        let mut w = window(2.0, 2.0);
+        w.set_flip_y(true);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 500.0])));
+        w.inner.push_input(Input::Move(Motion::Touch(TouchArgs::new(0, 0, [0.5, 0.25], 1.0, Touch::Move))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.0, 50.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::Touch(
+            TouchArgs::new(0, 0, [0.5, 0.75], 1.0, Touch::Move))))));
+    }
+
+    #[test]
+    fn has_pending_events() {
+        let mut w = window(2.0, 2.0);
+        assert!(!w.has_pending_events());
+        w.inner.push_input(Input::Move(Motion::MouseCursor([2.0, 2.0])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([4.0, 4.0])));
+        assert!(w.has_pending_events());
+        assert!(w.has_pending_events());
+        assert_eq!(w.inner.events.len(), 1);
+        w.inject_event(Input::Focus(true));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(true))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([1.0, 1.0])))));
+        assert!(w.has_pending_events());
+        assert_eq!(w.wait_event(), input(Input::Move(Motion::MouseCursor([2.0, 2.0]))));
+        assert!(!w.has_pending_events());
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn integer_sizes() {
+        let mut w = FakeDpiWindow::new(MockWindow::new([1367, 767]), 1.5);
+        assert_eq!(w.size_u32(), [911, 511]);
+        assert_eq!(w.draw_size_u32(), [1367, 767]);
+        w.set_rounding(RoundingMode::Ceil);
+        assert_eq!(w.size_u32(), [912, 512]);
+        assert_eq!(w.draw_size_u32(), [1368, 768]);
+        w.set_rounding(RoundingMode::Floor);
+        assert_eq!(w.size_u32(), [911, 511]);
+        assert_eq!(w.draw_size_u32(), [1366, 766]);
+        assert_eq!(w.size_u32(), [w.size().width as u32, w.size().height as u32]);
+    }
+
+    #[test]
+    fn set_size_hook() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut w = window(2.0, 1.5);
+        let sizes = Rc::new(RefCell::new(vec![]));
+        let s = sizes.clone();
+        w.set_size_hook(Some(Box::new(move |size| s.borrow_mut().push(size))));
+        w.set_size([150, 100]);
+        assert_eq!(*sizes.borrow(), vec![Size {width: 300.0, height: 150.0}]);
+    }
+
+    #[test]
+    fn size_round_trip() {
+        for &(dpi_x, dpi_y) in &[(2.0, 2.0), (2.0, 1.5), (1.25, 3.0), (0.3, 7.0)] {
+            let mut w = window(dpi_x, dpi_y);
+            for &(width, height) in &[(0.0, 0.0), (1.0, 1.0), (300.0, 200.0), (911.0, 1367.0)] {
+                let size = Size {width, height};
+                let physical = w.logical_to_physical_size(size);
+                assert_eq!(physical, Size {width: width * dpi_x, height: height * dpi_y});
+                w.set_rounding(RoundingMode::None);
+                let q = w.physical_to_logical_size(physical);
+                assert!((q.width - width).abs() < 1e-9 && (q.height - height).abs() < 1e-9);
+                w.set_rounding(RoundingMode::Round);
+                assert_eq!(w.physical_to_logical_size(physical), size);
+            }
+        }
+    }
+
+    #[test]
+    fn allowed_dpi_steps() {
+        let mut w = window(2.0, 2.0);
+        w.set_allowed_dpi_steps(vec![1.0, 1.25, 1.5, 1.75, 2.0]).unwrap();
+        assert_eq!(w.dpi(), 2.0);
+        w.set_dpi(1.6).unwrap();
+        assert_eq!(w.dpi(), 1.5);
+        w.set_dpi_xy(1.7, 7.0).unwrap();
+        assert_eq!([w.dpi_x(), w.dpi_y()], [1.75, 2.0]);
+        assert_eq!(w.set_allowed_dpi_steps(vec![0.0]), Err(InvalidDpi(0.0)));
+        w.clear_allowed_dpi_steps();
+        assert!(w.allowed_dpi_steps().is_empty());
+        w.set_dpi(1.6).unwrap();
+        assert_eq!(w.dpi(), 1.6);
+    }
+
+    #[test]
+    fn event_stats() {
+        let mut w = window(2.0, 2.0);
+        w.inject_event(Input::Focus(true));
+        w.poll_event();
+        assert_eq!(w.event_stats(), EventStats::default());
+
+        w.set_collect_event_stats(true);
+        w.inject_event(Input::Move(Motion::MouseCursor([1.0, 1.0])));
+        w.inject_event(Input::Move(Motion::MouseCursor([2.0, 1.0])));
+        w.inject_event(Input::Move(Motion::MouseRelative([1.0, 1.0])));
+        w.inject_event(Input::Move(Motion::MouseScroll([0.0, 1.0])));
+        w.inject_event(Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [600, 600]}));
+        w.inject_event(Input::Focus(false));
+        w.inject_event(Input::Text("a".into()));
+        while w.poll_event().is_some() {}
+        assert_eq!(w.event_stats(), EventStats {
+            cursor: 2,
+            relative: 1,
+            scroll: 1,
+            touch: 0,
+            resize: 1,
+            passthrough: 2,
+        });
+        w.reset_event_stats();
+        assert_eq!(w.event_stats(), EventStats::default());
+    }
+
+    #[test]
+    fn dpi_hotkey() {
+        let key = |state| Input::Button(ButtonArgs {state, button: Button::Keyboard(Key::F1), scancode: None});
+        let mut w = window(2.0, 2.0);
+        w.set_dpi_hotkey(Some(Key::F1));
+        w.inner.push_input(key(ButtonState::Press));
+        assert_eq!(w.poll_event(), Some(input(key(ButtonState::Press))));
+        assert_eq!(w.dpi(), 2.25);
+        assert!(matches!(w.poll_event(), Some(Event::Input(Input::Resize(_), None))));
+
+        w.set_swallow_dpi_hotkey(true);
+        w.set_dpi_hotkey_step(-0.5);
+        w.inner.push_input(key(ButtonState::Press));
+        w.inner.push_input(key(ButtonState::Release));
+        w.inner.push_input(Input::Focus(true));
+        assert!(matches!(w.poll_event(), Some(Event::Input(Input::Resize(_), None))));
+        assert_eq!(w.dpi(), 1.75);
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(true))));
+    }
+
+    #[test]
+    fn defaults() {
+        let w: FakeDpiWindow<MockWindow> = FakeDpiWindow::default();
+        assert_eq!(w.dpi(), 2.0);
+        assert_eq!(w.get_rounding(), RoundingMode::None);
+        assert!(w.get_enabled());
+        assert_eq!(w.size(), Size {width: 400.0, height: 300.0});
+        let config = FakeDpiConfig::default();
+        assert_eq!([config.dpi_x, config.dpi_y], [2.0, 2.0]);
+        assert_eq!(config.rounding, RoundingMode::None);
+    }
+
+    #[test]
+    fn with_dpi() {
+        let mut w = window(2.0, 1.5);
+        let size = w.with_dpi(3.0, |w| {
+            assert_eq!(w.dpi(), 3.0);
+            w.size()
+        }).unwrap();
+        assert_eq!(size, Size {width: 200.0, height: 200.0});
+        assert_eq!([w.dpi_x(), w.dpi_y()], [2.0, 1.5]);
+        assert!(w.poll_event().is_some());
+
+        assert_eq!(w.with_dpi_quiet(1.0, |w| w.size()).unwrap(), Size {width: 600.0, height: 600.0});
+        assert_eq!(w.poll_event(), None);
+        assert_eq!(w.with_dpi(0.0, |_| ()), Err(InvalidDpi(0.0)));
+
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            w.with_dpi(4.0, |_| panic!("closure panicked")).unwrap()
+        }));
+        assert!(res.is_err());
+        assert_eq!([w.dpi_x(), w.dpi_y()], [2.0, 1.5]);
+    }
+
+    #[test]
+    fn auto_close_after() {
+        let mut w = window(2.0, 2.0);
+        w.swap_buffers();
+        w.set_auto_close_after(Some(3));
+        let mut frames = 0;
+        while !w.should_close() {
+            w.swap_buffers();
+            frames += 1;
+        }
+        assert_eq!(frames, 3);
+        assert!(!w.inner.should_close);
+
+        w.set_auto_close_after(None);
+        assert!(!w.should_close());
+        w.set_should_close(true);
+        assert!(w.should_close());
+    }
+
+    #[test]
+    fn letterbox() {
+        let mut w = window(2.0, 2.0);
+        w.inner.size = Size {width: 800.0, height: 600.0};
+        w.inner.draw_size = w.inner.size;
+        w.set_letterbox(Some(Size {width: 0.0, height: 1080.0}));
+        assert_eq!((w.letterbox(), w.letterbox_viewport()), (None, None));
+        assert_eq!(w.size(), Size {width: 400.0, height: 300.0});
+
+        w.set_letterbox(Some(Size {width: 1920.0, height: 1080.0}));
+        assert_eq!(w.letterbox_viewport(), Some((0.4166666666666667, [0.0, 75.0])));
+        assert_eq!(w.size(), Size {width: 1920.0, height: 1080.0});
+        assert_eq!(w.draw_size(), Size {width: 800.0, height: 450.0});
+        w.inner.push_input(Input::Move(Motion::MouseCursor([400.0, 300.0])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 30.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([960.0, 540.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([240.0, -108.0])))));
+        assert!(w.contains_physical([100.0, 75.0]));
+        assert!(!w.contains_physical([100.0, 30.0]));
+        assert!(w.contains_physical([100.0, 525.0]));
+        assert!(!w.contains_physical([100.0, 525.5]));
+        assert_eq!(w.unmap_cursor_logical([960.0, 540.0]), [400.0, 300.0]);
+        w.set_clamp_cursor(true);
+        assert_eq!(w.map_cursor_physical([100.0, 30.0]), [240.0, 0.0]);
+
+        w.inner.size = Size {width: 1000.0, height: 1080.0};
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [1000.0, 1080.0], draw_size: [1000, 1080]}));
+        assert_eq!(w.letterbox_viewport(), Some((0.5208333333333334, [0.0, 258.75])));
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [1920.0, 1080.0],
+            draw_size: [1000, 563],
+        }))));
+        w.set_letterbox(None);
+        assert_eq!(w.size(), Size {width: 500.0, height: 540.0});
+    }
+
+    #[test]
+    fn origin() {
+        use input::{Touch, TouchArgs};
+
+        let mut w = window(2.0, 2.0);
+        w.set_origin([10.0, 30.0]);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 100.0])));
+        w.inner.push_input(Input::Move(Motion::Touch(TouchArgs::new(0, 0, [0.5, 0.5], 1.0, Touch::Move))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([40.0, 20.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::Touch(
+            TouchArgs::new(0, 0, [0.5 - 10.0 / 300.0, 0.4], 1.0, Touch::Move))))));
+        assert!(w.contains_physical([20.0, 60.0]));
+        assert!(!w.contains_physical([18.0, 60.0]));
+        w.set_cursor_space(CoordinateSpace::Physical);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 100.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([80.0, 40.0])))));
+    }
+
+    #[test]
+    fn paired_scroll() {
+        let mut w = window(2.0, 2.0);
+        w.set_paired_scroll(Some(0.05));
+        w.inner.push_input(Input::Move(Motion::MouseScroll([0.0, 1.0])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([10.0, 10.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseScroll([0.0, 1.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseScroll([0.0, 20.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([5.0, 5.0])))));
+        w.set_scale_scroll(true);
+        w.inner.push_input(Input::Move(Motion::MouseScroll([0.0, 80.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseScroll([0.0, 40.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseScroll([0.0, 2.0])))));
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn scroll_step() {
+        let mut w = window(2.0, 2.0);
+        w.set_scroll_step(Some(-1.0));
+        assert_eq!(w.scroll_step(), None);
+        w.set_scroll_step(Some(1.0));
+        for d in [0.25, 0.25, 0.25] {w.inner.push_input(Input::Move(Motion::MouseScroll([0.0, d])))}
+        w.inner.push_input(Input::Move(Motion::MouseScroll([-0.5, 0.5])));
+        w.inner.push_input(Input::Move(Motion::MouseScroll([-0.75, 0.25])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseScroll([0.0, 1.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseScroll([-1.0, 0.0])))));
+        assert_eq!(w.poll_event(), None);
+        assert_eq!(w.scroll_accum, [-0.25, 0.5]);
+    }
+
+    #[test]
+    fn derive_relative_motion() {
+        let mut w = window(2.0, 2.0);
+        w.set_derive_relative_motion(true);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([130.0, 40.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.0, 25.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([65.0, 20.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseRelative([15.0, -5.0])))));
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn into_boxed() {
+        let mut w = window(2.0, 2.0);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        let mut w: Box<dyn Window> = w.into_boxed();
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.0, 25.0])))));
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn strict() {
+        let mut w = window(2.0, 2.0);
+        w.inner.size = Size {width: f64::INFINITY, height: 600.0};
+        assert_eq!(w.size().width, f64::INFINITY);
+        w.set_strict(true);
+        w.inner.size = Size {width: 600.0, height: 600.0};
+        w.inject_event(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.0, 25.0])))));
+    }
+
+    #[test]
+    #[should_panic(expected = "mapped cursor position")]
+    fn strict_non_finite_cursor() {
+        let mut w = window(2.0, 2.0);
+        w.set_strict(true);
+        w.inject_event(Input::Move(Motion::MouseCursor([f64::NAN, 50.0])));
+        w.poll_event();
+    }
+
+    #[test]
+    #[should_panic(expected = "mapped window size")]
+    fn strict_non_finite_size() {
+        let mut w = window(2.0, 2.0);
+        w.set_strict(true);
+        w.inner.size = Size {width: f64::INFINITY, height: 600.0};
+        w.size();
+    }
+
+    #[test]
+    fn map_cursor_physical() {
+        let mut w = window(2.0, 2.0);
+        w.set_flip_y(true);
+        w.set_origin([10.0, 5.0]);
+        w.set_input_dpi(Some(4.0)).unwrap();
+        for space in [CoordinateSpace::Logical, CoordinateSpace::Physical] {
+            w.set_cursor_space(space);
+            let p = [100.0, 40.0];
+            w.inner.push_input(Input::Move(Motion::MouseCursor(p)));
+            let mapped = w.map_cursor_physical(p);
+            assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor(mapped)))));
+            assert_eq!(w.unmap_cursor_logical(mapped), p);
+        }
+        w.set_rounding(RoundingMode::Floor);
+        w.set_cursor_space(CoordinateSpace::Logical);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([101.0, 41.0])));
+        let mapped = w.map_cursor_physical([101.0, 41.0]);
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor(mapped)))));
+    }
+
+    #[test]
+    fn logical_bounds() {
+        let mut w = window(2.0, 2.0);
+        assert_eq!(w.logical_bounds(&[]), None);
+        let points = [[100.0, 40.0], [20.0, 300.0], [420.0, 64.0]];
+        assert_eq!(w.logical_bounds(&points[..1]), Some((50.0, 20.0, 50.0, 20.0)));
+        assert_eq!(w.logical_bounds(&points), Some((10.0, 20.0, 210.0, 150.0)));
+        w.set_origin([10.0, 5.0]);
+        w.set_flip_y(true);
+        assert_eq!(w.logical_bounds(&points), Some((0.0, 145.0, 200.0, 275.0)));
+    }
+
+    #[test]
+    fn detect_size_changes() {
+        let mut w = window(2.0, 2.0);
+        w.inner.size = Size {width: 800.0, height: 600.0};
+        assert_eq!(w.poll_event(), None);
+        w.set_detect_size_changes(true);
+        assert_eq!(w.poll_event(), None);
+        w.inner.size = Size {width: 1000.0, height: 600.0};
+        w.inner.push_input(Input::Move(Motion::MouseCursor([10.0, 10.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([5.0, 5.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [500.0, 300.0],
+            draw_size: [1000, 600],
+        }))));
+        assert_eq!(w.poll_event(), None);
+        // A received resize event updates the last seen size.
+        w.inner.size = Size {width: 1200.0, height: 600.0};
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [1200.0, 600.0], draw_size: [1200, 600]}));
+        assert!(matches!(w.poll_event(), Some(Event::Input(Input::Resize(_), _))));
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn dpi_newtype() {
+        assert_eq!(Dpi::new(1.5).map(Dpi::get), Ok(1.5));
+        assert_eq!(*Dpi::from(2.0), 2.0);
+        assert_eq!(f64::from(Dpi::from(2.0)), 2.0);
+        assert_eq!(Dpi::from(1.25).to_string(), "1.25");
+        for &dpi in &[0.0, -1.0, f64::INFINITY, f64::NAN] {
+            assert!(Dpi::new(dpi).is_err());
+            assert!(std::panic::catch_unwind(|| Dpi::from(dpi)).is_err());
+        }
+        let mut w = window(2.0, 2.0);
+        w.set_dpi(Dpi::new(1.5).unwrap()).unwrap();
+        assert_eq!(w.dpi(), 1.5);
+    }
+
+    #[test]
+    fn divergent_resize() {
+        let mut w = window(2.0, 2.0);
+        w.inject_divergent_resize([-10, 4]);
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [300.0, 300.0],
+            draw_size: [590, 604],
+        }))));
+        assert_eq!(w.poll_event(), None);
+        w.set_dpi(1.5).unwrap();
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [400.0, 400.0],
+            draw_size: [600, 600],
+        }))));
+    }
+
+    #[test]
+    fn input_scaling_table() {
+        use input::{CloseArgs, ControllerAxisArgs, FileDrag, Touch, TouchArgs};
+
+        let samples = vec![
+            Input::Button(ButtonArgs {state: ButtonState::Press, button: Button::Keyboard(Key::A), scancode: None}),
+            Input::Move(Motion::MouseCursor([100.0, 50.0])),
+            Input::Move(Motion::MouseRelative([100.0, 50.0])),
+            Input::Move(Motion::MouseScroll([100.0, 50.0])),
+            Input::Move(Motion::ControllerAxis(ControllerAxisArgs::new(0, 0, 0.5))),
+            Input::Move(Motion::Touch(TouchArgs::new(0, 0, [0.5, 0.5], 1.0, Touch::Move))),
+            Input::Text("a".into()),
+            Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [600, 600]}),
+            Input::Focus(true),
+            Input::Cursor(true),
+            Input::FileDrag(FileDrag::Hover("a.txt".into())),
+            Input::Close(CloseArgs),
+        ];
+        assert_eq!(samples.len(), INPUT_SCALING.len());
+        let mut w = window(2.0, 2.0);
+        w.set_check_size_ratio(false);
+        for e in samples {
+            let kind = InputKind::of(&e);
+            let entries: Vec<_> = INPUT_SCALING.iter().filter(|entry| entry.0 == kind).collect();
+            assert_eq!(entries.len(), 1, "{:?}", kind);
+            w.inject_event(e.clone());
+            let mapped = w.poll_event();
+            assert_eq!(mapped != Some(input(e)), entries[0].1, "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn poll_iter() {
+        let mut w = window(2.0, 2.0);
+        w.set_dpi(1.5).unwrap();
+        w.set_dpi(2.0).unwrap();
+        w.inject_event(Input::Move(Motion::MouseCursor([10.0, 20.0])));
+        w.inner.push_input(Input::Move(Motion::MouseRelative([4.0, 6.0])));
+        w.inner.events.push_back(Event::Input(Input::Move(Motion::MouseCursor([30.0, 40.0])), Some(7)));
+        let events: Vec<_> = w.poll_iter().collect();
+        assert_eq!(events, vec![
+            (Input::Resize(ResizeArgs {window_size: [300.0, 300.0], draw_size: [600, 600]}), None),
+            (Input::Move(Motion::MouseCursor([5.0, 10.0])), None),
+            (Input::Move(Motion::MouseRelative([2.0, 3.0])), None),
+            (Input::Move(Motion::MouseCursor([15.0, 20.0])), Some(7)),
+        ]);
+        w.inner.push_input(Input::Focus(true));
+        assert_eq!(w.poll_iter().count(), 1);
+    }
+
+    #[test]
+    fn orientation() {
+        use input::{Touch, TouchArgs};
+
+        let expected = [
+            (Orientation::Rotate0, [800.0, 600.0], [100.0, 50.0], [0.25, 0.5]),
+            (Orientation::Rotate90, [600.0, 800.0], [550.0, 100.0], [0.5, 0.25]),
+            (Orientation::Rotate180, [800.0, 600.0], [700.0, 550.0], [0.75, 0.5]),
+            (Orientation::Rotate270, [600.0, 800.0], [50.0, 700.0], [0.5, 0.75]),
+        ];
+        for &(orientation, size, cursor, touch) in &expected {
+            let mut w = window(2.0, 2.0);
+            w.inner.size = Size {width: 1600.0, height: 1200.0};
+            w.set_orientation(orientation);
+            assert_eq!(w.get_orientation(), orientation);
+            assert_eq!(w.size(), size.into());
+            assert_eq!(w.draw_size(), [size[0] * 2.0, size[1] * 2.0].into());
+            if orientation.swaps_axes() {
+                assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+                    window_size: size,
+                    draw_size: [size[0] as u32 * 2, size[1] as u32 * 2],
+                }))));
+            }
+            w.inner.push_input(Input::Move(Motion::MouseCursor([200.0, 100.0])));
+            w.inner.push_input(Input::Move(Motion::Touch(TouchArgs::new(0, 0, [0.25, 0.5], 1.0, Touch::Move))));
+            w.inner.push_input(Input::Resize(ResizeArgs {window_size: [1600.0, 1200.0], draw_size: [1600, 1200]}));
+            assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor(cursor)))));
+            assert_eq!(w.unmap_cursor_logical(cursor), [200.0, 100.0]);
+            assert!(w.contains_physical([200.0, 100.0]));
+            assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::Touch(
+                TouchArgs::new(0, 0, touch, 1.0, Touch::Move))))));
+            assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+                window_size: size,
+                draw_size: [size[0] as u32 * 2, size[1] as u32 * 2],
+            }))));
+            w.set_size(size);
+            assert_eq!(w.inner.size, Size {width: 1600.0, height: 1200.0});
+        }
+    }
+
+    #[test]
+    fn last_event() {
+        let mut w = window(2.0, 2.0);
+        assert_eq!(w.last_event(), None);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        w.poll_event();
+        assert_eq!(w.last_event(), Some(&Input::Move(Motion::MouseCursor([50.0, 25.0]))));
+        assert_eq!(w.poll_event(), None);
+        assert_eq!(w.last_event(), Some(&Input::Move(Motion::MouseCursor([50.0, 25.0]))));
+        w.inner.push_input(Input::Focus(false));
+        w.wait_event();
+        assert_eq!(w.last_event(), Some(&Input::Focus(false)));
+        w.reset_config();
+        assert_eq!(w.last_event(), None);
+    }
+
+    #[test]
+    fn last_resize_was_synthetic() {
+        let mut w = window(2.0, 2.0);
+        assert_eq!(w.last_resize_was_synthetic(), None);
+        w.set_dpi(1.5).unwrap();
+        assert!(matches!(w.poll_event(), Some(Event::Input(Input::Resize(_), None))));
+        assert_eq!(w.last_resize_was_synthetic(), Some(true));
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [600, 600]}));
+        assert!(matches!(w.poll_event(), Some(Event::Input(Input::Resize(_), None))));
+        assert_eq!(w.last_resize_was_synthetic(), Some(false));
+        w.inner.push_input(Input::Focus(true));
+        w.poll_event();
+        assert_eq!(w.last_resize_was_synthetic(), Some(false));
+        w.set_dpi(2.0).unwrap();
+        w.wait_event();
+        assert_eq!(w.last_resize_was_synthetic(), Some(true));
+    }
+
+    #[test]
+    fn suppress_synthetic_events() {
+        let mut w = window(2.0, 2.0);
+        w.set_dpi(1.5).unwrap();
+        w.inject_event(Input::Focus(false));
+        w.inner.push_input(Input::Focus(true));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([30.0, 60.0])));
+        {
+            let mut guard = w.suppress_synthetic_events();
+            assert!(guard.has_pending_events());
+            assert_eq!(guard.poll_event(), Some(input(Input::Focus(true))));
+            assert_eq!(guard.wait_event(), input(Input::Move(Motion::MouseCursor([20.0, 40.0]))));
+            assert_eq!(guard.poll_event(), None);
+            assert!(!guard.has_pending_events());
+        }
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [400.0, 400.0],
+            draw_size: [600, 600],
+        }))));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(false))));
+        assert_eq!(w.poll_event(), None);
+    }
+
+    #[test]
+    fn event_channel() {
+        use std::sync::mpsc;
+
+        let mut w = window(2.0, 2.0);
+        let (sender, receiver) = mpsc::channel();
+        w.set_event_channel(Some(sender));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([30.0, 60.0])));
+        w.set_dpi(1.5).unwrap();
+        let resize = Input::Resize(ResizeArgs {window_size: [400.0, 400.0], draw_size: [600, 600]});
+        assert_eq!(w.poll_event(), Some(input(resize.clone())));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([20.0, 40.0])))));
+        let received = std::thread::spawn(move || receiver.iter().collect::<Vec<_>>());
+        w.inner.push_input(Input::Focus(true));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(true))));
+        w.set_event_channel(None);
+        assert_eq!(received.join().unwrap(), vec![
+            (resize, None),
+            (Input::Move(Motion::MouseCursor([20.0, 40.0])), None),
+            (Input::Focus(true), None),
+        ]);
+
+        let (sender, receiver) = mpsc::channel();
+        w.set_event_channel(Some(sender));
+        drop(receiver);
+        w.inner.push_input(Input::Focus(false));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(false))));
+        assert!(w.hooks.event_channel.is_none());
+    }
+
+    #[test]
+    fn last_cursor() {
+        let mut w = window(2.0, 2.0);
+        assert_eq!(w.last_cursor_physical(), None);
+        assert_eq!(w.last_cursor_logical(), None);
+        w.inject_event(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        w.poll_event();
+        assert_eq!(w.last_cursor_physical(), Some([100.0, 50.0]));
+        assert_eq!(w.last_cursor_logical(), Some([50.0, 25.0]));
+        w.set_dpi(4.0).unwrap();
+        assert_eq!(w.last_cursor_logical(), Some([25.0, 12.5]));
+        w.inject_event(Input::Focus(true));
+        w.poll_event();
+        assert_eq!(w.last_cursor_physical(), Some([100.0, 50.0]));
+    }
+
+    #[test]
+    fn last_resize_mapping() {
+        let mut w = window(2.0, 1.5);
+        assert_eq!(w.last_resize_mapping(), None);
+        let incoming = ResizeArgs {window_size: [800.0, 600.0], draw_size: [800, 600]};
+        w.inner.push_input(Input::Resize(incoming));
+        w.poll_event();
+        assert_eq!(w.last_resize_mapping(), Some((incoming, ResizeArgs {
+            window_size: [400.0, 400.0],
+            draw_size: [800, 600],
+        })));
+        w.set_dpi(1.0).unwrap();
+        w.poll_event();
+        assert_eq!(w.last_resize_mapping().map(|(incoming, _)| incoming), Some(incoming));
+        w.reset_config();
+        assert_eq!(w.last_resize_mapping(), None);
+    }
+
+    #[test]
+    fn map_events() {
+        fn configured() -> FakeDpiWindow<MockWindow> {
+            let mut w = window(2.0, 2.0);
+            w.set_rounding(RoundingMode::Floor);
+            w.set_flip_y(true);
+            w.set_origin([5.0, 0.0]);
+            w.set_clamp_cursor(true);
+            w.set_paired_scroll(Some(10.0));
+            w.set_derive_relative_motion(true);
+            w.set_dpi_hotkey(Some(Key::F2));
+            w.set_swallow_dpi_hotkey(true);
+            w.set_monitor_layout(vec![
+                Monitor {dpi: 2.0, position: [0.0, 0.0], size: [300.0, 600.0]},
+                Monitor {dpi: 1.0, position: [300.0, 0.0], size: [300.0, 600.0]},
+            ]).unwrap();
+            w
+        }
+        let press = |key| Input::Button(ButtonArgs {
+            state: ButtonState::Press,
+            button: Button::Keyboard(key),
+            scancode: None,
+        });
+        let events = vec![
+            Input::Move(Motion::MouseCursor([101.0, 51.0])),
+            Input::Move(Motion::MouseRelative([3.0, 4.0])),
+            press(Key::A),
+            Input::Move(Motion::MouseCursor([400.0, 300.0])),
+            press(Key::F2),
+            Input::Focus(true),
+            Input::Resize(ResizeArgs {window_size: [800.0, 400.0], draw_size: [800, 400]}),
+            Input::Move(Motion::MouseScroll([0.0, 1.0])),
+            Input::Move(Motion::MouseCursor([-20.0, 30.0])),
+        ];
+
+        let mut w = configured();
+        let mapped = w.map_events(&events);
+        let mut polled_window = configured();
+        for e in &events {polled_window.inject_event(e.clone())}
+        let polled: Vec<_> = polled_window.poll_iter().map(|(e, _)| e).collect();
+        assert_eq!(mapped.len(), polled.len());
+        for (mapped, polled) in mapped.iter().zip(&polled) {assert_eq!(mapped, polled)}
+        assert_eq!(mapped[0], Input::Move(Motion::MouseCursor([45.0, 274.0])));
+        assert!(mapped.len() > events.len());
+        assert_eq!((w.dpi(), w.active_monitor()), (polled_window.dpi(), polled_window.active_monitor()));
+        assert_eq!(w.dpi(), 1.25);
+
+        // Discrete scroll steps drop the scroll event, like when polling.
+        w.set_scroll_step(Some(2.0));
+        assert_eq!(w.map_events(&events[7..8]), vec![]);
+        polled_window.set_scroll_step(Some(2.0));
+        polled_window.inject_event(events[7].clone());
+        assert_eq!(polled_window.poll_event(), None);
+    }
+
+    #[test]
+    fn map_events_diff() {
+        use input::Button;
+
+        let mut w = window(2.0, 2.0);
+        let events = vec![
+            Input::Move(Motion::MouseCursor([100.0, 50.0])),
+            Input::Button(ButtonArgs {state: ButtonState::Press, button: Button::Keyboard(Key::A), scancode: None}),
+            Input::Move(Motion::MouseCursor([0.0, 0.0])),
+            Input::Move(Motion::MouseScroll([0.0, 1.0])),
+            Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [600, 600]}),
+        ];
+        let diff = w.map_events_diff(&events);
+        assert_eq!(diff.iter().map(|&(_, changed)| changed).collect::<Vec<_>>(), [true, false, false, false, true]);
+        assert_eq!(diff.into_iter().map(|(e, _)| e).collect::<Vec<_>>(), w.map_events(&events));
+        // The companion scroll event is generated by the wrapper.
+        w.set_paired_scroll(Some(10.0));
+        let diff = w.map_events_diff(&events[3..4]);
+        assert_eq!(diff.iter().map(|&(_, changed)| changed).collect::<Vec<_>>(), [false, true]);
+    }
+
+    #[test]
+    fn suspicious_set_size() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut w = window(2.0, 2.0);
+        assert!(!w.is_suspicious_size([600.0, 600.0].into()));
+        w.set_size_check_tolerance(Some(2.0));
+        assert!(w.is_suspicious_size([600.0, 600.0].into()));
+        assert!(w.is_suspicious_size([601.0, 598.0].into()));
+        assert!(!w.is_suspicious_size([300.0, 300.0].into()));
+        assert!(!w.is_suspicious_size([610.0, 600.0].into()));
+        w.set_dpi(1.0).unwrap();
+        assert!(!w.is_suspicious_size([600.0, 600.0].into()));
+
+        let mut w = window(2.0, 2.0);
+        let warnings = Rc::new(RefCell::new(vec![]));
+        let log = warnings.clone();
+        w.set_suspicious_size_callback(Some(Box::new(move |val, size| log.borrow_mut().push((val, size)))));
+        w.set_size([600, 600]);
+        assert!(warnings.borrow().is_empty());
+        w.set_size_check_tolerance(Some(2.0));
+        w.set_size([200, 100]);
+        assert!(warnings.borrow().is_empty());
+        w.set_size([400, 200]);
+        assert_eq!(*warnings.borrow(), [(Size {width: 400.0, height: 200.0}, Size {width: 200.0, height: 100.0})]);
+    }
+
+    #[test]
+    fn start_hidden() {
+        let settings = FakeDpiWindowSettings::new(WindowSettings::new("test", [300, 300]));
+        let w = settings.clone().build::<MockWindow>().unwrap();
+        assert!(w.is_visible());
+        assert!(w.inner.visible);
+        let w = settings.clone().build::<MockWindow>().unwrap().start_hidden();
+        assert!(!w.is_visible());
+        assert!(!w.inner.visible);
+
+        let settings = settings.start_hidden(true);
+        assert!(settings.get_start_hidden());
+        let mut w = settings.build::<MockWindow>().unwrap();
+        assert!(!w.is_visible());
+        assert!(!w.inner.visible);
+        w.show();
+        assert!(w.is_visible());
+        assert!(w.inner.visible);
+        w.reset_config();
+        assert!(w.is_visible());
+    }
+
+    #[test]
+    fn dpi_for() {
+        assert_eq!(super::dpi_for([1600.0, 900.0].into(), [800.0, 600.0].into()), [2.0, 1.5]);
+        let mut w = window(1.0, 1.0);
+        let [dpi_x, dpi_y] = super::dpi_for(w.physical_size(), [240.0, 400.0].into());
+        w.set_dpi_xy(dpi_x, dpi_y).unwrap();
+        assert_eq!(w.size(), Size {width: 240.0, height: 400.0});
+
+        let dpi = super::dpi_for([600.0, 0.0].into(), [0.0, 0.0].into());
+        assert_eq!(dpi, [0.0, 0.0]);
+        assert!(w.set_dpi_xy(dpi[0], 1.0).is_err());
+        assert!(w.set_dpi_xy(1.0, dpi[1]).is_err());
+    }
+
+    #[test]
+    fn scale_mask() {
+        let mut mask = ScaleMask::default();
+        assert!(mask.contains(ScaleMask::CURSOR | ScaleMask::RESIZE));
+        assert!(!mask.contains(ScaleMask::TOUCH));
+        mask.remove(ScaleMask::CURSOR);
+        assert!(!mask.contains(ScaleMask::CURSOR));
+        mask.insert(ScaleMask::TOUCH);
+        assert_eq!(mask & ScaleMask::TOUCH, ScaleMask::TOUCH);
+        assert_eq!(ScaleMask::ALL.bits(), 0b11_1111);
+
+        let cursor = Input::Move(Motion::MouseCursor([100.0, 50.0]));
+        let relative = Input::Move(Motion::MouseRelative([100.0, 50.0]));
+        let resize = Input::Resize(ResizeArgs {window_size: [600.0, 600.0], draw_size: [600, 600]});
+        let mut w = window(2.0, 2.0);
+        w.set_scale_mask(ScaleMask::RESIZE);
+        assert_eq!(w.get_scale_mask(), ScaleMask::RESIZE);
+        assert_eq!(w.map_events(&[cursor.clone(), relative.clone(), resize.clone()]), vec![
+            cursor.clone(),
+            relative.clone(),
+            Input::Resize(ResizeArgs {window_size: [300.0, 300.0], draw_size: [600, 600]}),
+        ]);
+        w.set_scale_mask(ScaleMask::CURSOR);
+        assert_eq!(w.map_events(&[cursor, relative.clone(), resize.clone()]), vec![
+            Input::Move(Motion::MouseCursor([50.0, 25.0])),
+            relative,
+            resize,
+        ]);
+    }
+
+    #[test]
+    fn map_generic_event() {
+        let mut e = Event::Input(Input::Move(Motion::MouseCursor([100.0, 50.0])), Some(3));
+        super::map_generic_event(2.0, &mut e);
+        assert_eq!(e, Event::Input(Input::Move(Motion::MouseCursor([50.0, 25.0])), Some(3)));
+        let mut e = input(Input::Resize(ResizeArgs {window_size: [600.0, 400.0], draw_size: [600, 400]}));
+        super::map_generic_event(2.0, &mut e);
+        assert_eq!(e, input(Input::Resize(ResizeArgs {window_size: [300.0, 200.0], draw_size: [600, 400]})));
+        let mut e = input(Input::Move(Motion::MouseScroll([0.0, 1.0])));
+        super::map_generic_event(2.0, &mut e);
+        assert_eq!(e, input(Input::Move(Motion::MouseScroll([0.0, 1.0]))));
+    }
+
+    #[test]
+    fn poll_raw_event() {
+        let mut w = window(2.0, 2.0);
+        w.set_dpi(1.5).unwrap();
+        w.set_dpi(2.0).unwrap();
+        w.inject_event(Input::Move(Motion::MouseCursor([10.0, 20.0])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        assert_eq!(w.poll_raw_event(), Some(input(Input::Move(Motion::MouseCursor([100.0, 50.0])))));
+        assert!(matches!(w.poll_event(), Some(Event::Input(Input::Resize(_), _))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([5.0, 10.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.0, 25.0])))));
+        assert_eq!(w.poll_raw_event(), None);
+    }
+
+    #[test]
+    fn for_each_dpi() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut w = window(3.0, 3.0);
+        let sizes = w.for_each_dpi(&[1.0, 1.5, 2.0], |w, dpi| (dpi, w.size())).unwrap();
+        assert_eq!(sizes, vec![
+            (1.0, Size {width: 600.0, height: 600.0}),
+            (1.5, Size {width: 400.0, height: 400.0}),
+            (2.0, Size {width: 300.0, height: 300.0}),
+        ]);
+        assert_eq!(w.dpi(), 3.0);
+
+        let mut calls = 0;
+        assert!(w.for_each_dpi(&[1.0, 0.0], |_, _| calls += 1).is_err());
+        assert_eq!(calls, 0);
+
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            w.for_each_dpi(&[1.0, 2.0], |_, dpi| if dpi == 2.0 {panic!("test")}).unwrap();
+        }));
+        assert!(res.is_err());
+        assert_eq!(w.dpi(), 3.0);
+    }
+
+    #[test]
+    fn dpi_percent() {
+        let mut w = window(2.0, 2.0);
+        assert_eq!(w.dpi_percent(), 200);
+        w.set_dpi_percent(125).unwrap();
+        assert_eq!(w.dpi(), 1.25);
+        assert_eq!(w.dpi_percent(), 125);
+        w.set_dpi(1.333).unwrap();
+        assert_eq!(w.dpi_percent(), 133);
+        assert_eq!(w.set_dpi_percent(0), Err(InvalidDpi(0.0)));
+        w.set_dpi_percent(1000).unwrap();
+        assert_eq!(w.dpi_percent(), 800);
+    }
+
+    #[test]
+    fn set_same_dpi_is_noop() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let mut w = window(2.0, 2.0);
+        let calls = Rc::new(Cell::new(0));
+        let calls2 = calls.clone();
+        w.set_dpi_change_callback(Some(Box::new(move |_| calls2.set(calls2.get() + 1))));
+        w.set_dpi(2.0).unwrap();
+        w.set_dpi(9.0).unwrap();
+        w.set_dpi(8.0).unwrap();
+        assert_eq!(calls.get(), 1);
+        assert!(matches!(w.poll_event(), Some(Event::Input(Input::Resize(_), _))));
+        w.set_dpi(8.0).unwrap();
+        assert_eq!(w.poll_event(), None);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn current_transform() {
+        let mut w = window(2.0, 1.5);
+        let points = [[0.0, 0.0], [101.0, 51.0], [333.3, 777.7], [-20.0, 599.0]];
+        let check = |w: &FakeDpiWindow<MockWindow>| {
+            let t = w.current_transform();
+            for &p in &points {assert_eq!(t.apply(p), w.map_cursor_physical(p), "{:?}", t)}
+        };
+        check(&w);
+        w.set_flip_y(true);
+        w.set_origin([7.0, 3.0]);
+        w.set_rounding(RoundingMode::Floor);
+        check(&w);
+        w.set_orientation(Orientation::Rotate270);
+        w.set_clamp_cursor(true);
+        check(&w);
+        w.set_input_dpi(Some(3.0)).unwrap();
+        check(&w);
+        w.set_cursor_space(CoordinateSpace::Physical);
+        check(&w);
+        w.set_cursor_space(CoordinateSpace::Logical);
+        w.set_enabled(false);
+        check(&w);
+    }
+
+    #[test]
+    fn pause() {
+        let mut w = window(2.0, 2.0);
+        w.set_paused(true);
+        w.set_dpi(1.5).unwrap();
+        w.set_dpi(2.0).unwrap();
+        w.inject_event(Input::Focus(true));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        assert_eq!(w.poll_event(), None);
+        assert!(!w.has_pending_events());
+        w.inner.push_input(Input::Move(Motion::MouseCursor([10.0, 20.0])));
+        assert_eq!(w.wait_event_timeout(Duration::from_secs(0)), None);
+        assert!(w.inner.events.is_empty());
+        w.set_paused(false);
+        let events: Vec<_> = w.poll_iter().map(|(e, _)| e).collect();
+        assert_eq!(events, vec![
+            Input::Resize(ResizeArgs {window_size: [300.0, 300.0], draw_size: [600, 600]}),
+            Input::Focus(true),
+            Input::Move(Motion::MouseCursor([50.0, 25.0])),
+            Input::Move(Motion::MouseCursor([5.0, 10.0])),
+        ]);
+    }
+
+    #[test]
+    fn report_physical_size() {
+        let mut w = window(2.0, 2.0);
+        w.set_report_physical_size(true);
+        assert_eq!(w.size(), Size {width: 600.0, height: 600.0});
+        assert_eq!(w.draw_size(), Size {width: 600.0, height: 600.0});
+        assert_eq!(w.logical_size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [600.0, 600.0],
+            draw_size: [600, 600],
+        }))));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [800.0, 600.0], draw_size: [800, 600]}));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.0, 25.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [800.0, 600.0],
+            draw_size: [800, 600],
+        }))));
+        w.set_size([640, 480]);
+        assert_eq!(w.inner.size, Size {width: 640.0, height: 480.0});
+    }
+
+    #[test]
+    fn wait_event_returns_queued_events() {
+        let mut w = window(2.0, 2.0);
+        w.inject_event(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        w.inject_event(Input::Focus(true));
+        // The mock window panics when waiting without queued events.
+        assert_eq!(w.wait_event(), input(Input::Move(Motion::MouseCursor([50.0, 25.0]))));
+        assert_eq!(w.wait_event_timeout(Duration::from_secs(1)), Some(input(Input::Focus(true))));
+        w.set_dpi(1.5).unwrap();
+        assert!(matches!(w.wait_event(), Event::Input(Input::Resize(_), _)));
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn randomize_dpi() {
+        let mut w = window(1.0, 1.0);
+        w.set_dpi_seed(42);
+        let mut dpis = vec![];
+        for _ in 0..4 {
+            w.randomize_dpi(1.0, 3.0).unwrap();
+            dpis.push(w.dpi());
+        }
+        assert_eq!(dpis, [2.6286102902458195, 1.6376420801233222, 2.9677883363549773, 2.402271196269511]);
+        assert!(w.resize_pending);
+        w.set_dpi_seed(42);
+        w.randomize_dpi(3.0, 1.0).unwrap();
+        assert_eq!(w.dpi(), dpis[0]);
+        assert_eq!(w.randomize_dpi(0.0, 2.0), Err(InvalidDpi(0.0)));
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn dpi_jitter() {
+        let mut w = window(2.0, 2.0);
+        w.set_dpi_jitter(-0.1);
+        assert_eq!((w.dpi_jitter(), w.dpi()), (0.0, 2.0));
+        w.set_dpi_seed(42);
+        w.set_dpi_jitter(0.1);
+        let dpis: Vec<f64> = (0..4).map(|_| w.dpi()).collect();
+        assert_eq!(dpis, [2.062861029024582, 1.9637642080123323, 2.0967788336354976, 2.0402271196269512]);
+        assert_eq!(w.dpi_xy(), [2.0; 2]);
+        assert!(!w.resize_pending);
+        w.set_dpi_seed(42);
+        assert_eq!(w.dpi(), dpis[0]);
+        w.set_dpi_jitter(0.0);
+        assert_eq!(w.physical_to_logical([600.0, 300.0]), [300.0, 150.0]);
+    }
+
+    #[test]
+    fn zero_size() {
+        let mut w = window(2.0, 2.0);
+        w.inner.size = Size {width: 0.0, height: 600.0};
+        w.inner.draw_size = w.inner.size;
+        assert_eq!(w.size(), Size {width: 0.0, height: 300.0});
+        assert_eq!(w.draw_size(), Size {width: 0.0, height: 600.0});
+        assert_eq!(w.size_u32(), [0, 300]);
+        assert_eq!(w.size_at_dpi(1.5), Size {width: 0.0, height: 400.0});
+        assert_eq!(w.physical_to_logical_size(w.physical_size()), w.size());
+        assert_eq!(super::dpi_for(w.physical_size(), w.size()), [0.0, 2.0]);
+        w.set_clamp_cursor(true);
+        w.set_flip_y(true);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([40.0, 200.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([0.0, 200.0])))));
+
+        w.inner.size = Size {width: -1.0, height: f64::NAN};
+        assert_eq!(w.size(), Size {width: 0.0, height: 0.0});
+        assert_eq!(w.draw_size(), Size {width: 0.0, height: 0.0});
+        w.inner.push_input(Input::Move(Motion::MouseCursor([40.0, 20.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([0.0, 0.0])))));
+    }
+
+    #[test]
+    fn cursor_quantum() {
+        use input::{Touch, TouchArgs};
+
+        let mut w = window(2.0, 2.0);
+        w.set_cursor_quantum(Some(0.25));
+        assert_eq!(w.cursor_quantum(), Some(0.25));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([101.0, 50.3])));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([0.7, 0.2])));
+        w.inner.push_input(Input::Move(Motion::Touch(TouchArgs::new(0, 0, [0.1001, 0.5], 1.0, Touch::Move))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.5, 25.25])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([0.25, 0.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::Touch(
+            TouchArgs::new(0, 0, [30.0 / 300.0, 0.5], 1.0, Touch::Move))))));
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.current_transform().apply([101.0, 50.3]), [50.5, 25.25]);
+        w.set_cursor_quantum(Some(0.0));
+        assert_eq!(w.cursor_quantum(), None);
+    }
+
+    #[test]
+    fn input_precision() {
+        let mut w = window(2.0, 2.0);
+        assert_eq!(w.map_cursor_physical([101.3, 50.75]), [50.65, 25.375]);
+        w.set_input_precision(Some(1.0));
+        assert_eq!(w.input_precision(), Some(1.0));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([101.3, 50.75])));
+        w.inner.push_input(Input::Move(Motion::MouseRelative([0.4, -2.5])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.5, 25.5])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseRelative([0.0, -1.5])))));
+        assert_eq!(w.current_transform().apply([101.3, 50.75]), [50.5, 25.5]);
+        w.set_input_precision(Some(0.5));
+        assert_eq!(w.map_cursor_physical([101.3, 50.75]), [50.75, 25.5]);
+        w.set_input_precision(Some(f64::NAN));
+        assert_eq!(w.input_precision(), None);
+    }
+
+    #[test]
+    fn coordinate_mapper() {
+        struct NegateX;
+
+        impl CoordinateMapper for NegateX {
+            fn map_cursor(&self, pos: [f64; 2], dpi: [f64; 2]) -> [f64; 2] {[-pos[0] / dpi[0], pos[1] / dpi[1]]}
+        }
+
+        let mut w = window(2.0, 2.0);
+        w.set_coordinate_mapper(Some(Box::new(NegateX)));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        w.inner.push_input(Input::Move(Motion::MouseRelative([10.0, 4.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([-50.0, 25.0])))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseRelative([5.0, 2.0])))));
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        w.set_coordinate_mapper(None);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.0, 25.0])))));
+    }
+
+    #[test]
+    fn input_latency() {
+        let mut w = window(2.0, 2.0);
+        let latency = Duration::from_millis(20);
+        w.set_input_latency(latency);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        w.inject_event(Input::Focus(true));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(true))));
+        assert_eq!(w.poll_event(), None);
+        assert!(!w.has_pending_events());
+        std::thread::sleep(latency);
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.0, 25.0])))));
+
+        w.inner.push_input(Input::Focus(false));
+        let start = Instant::now();
+        assert_eq!(w.wait_event(), input(Input::Focus(false)));
+        assert!(start.elapsed() >= latency);
+    }
+
+    #[test]
+    fn dpi_threshold() {
+        let mut w = window(2.0, 2.0);
+        w.set_dpi_threshold(Some(Size {width: 500.0, height: 500.0}));
+        assert_eq!(w.poll_event(), None);
+        assert_eq!(w.effective_dpi(), 2.0);
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+
+        w.inner.size = Size {width: 400.0, height: 600.0};
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [400.0, 600.0], draw_size: [400, 600]}));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {window_size: [400.0, 600.0], draw_size: [400, 600]}))));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([100.0, 50.0])))));
+        assert_eq!(w.effective_dpi(), 1.0);
+        assert_eq!(w.dpi(), 2.0);
+        assert_eq!(w.size(), Size {width: 400.0, height: 600.0});
+
+        w.inner.size = Size {width: 800.0, height: 600.0};
+        w.inner.push_input(Input::Resize(ResizeArgs {window_size: [800.0, 600.0], draw_size: [800, 600]}));
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {window_size: [400.0, 300.0], draw_size: [800, 600]}))));
+        assert_eq!(w.effective_dpi(), 2.0);
+
+        w.set_dpi_threshold(Some(Size {width: 1000.0, height: 0.0}));
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {window_size: [800.0, 600.0], draw_size: [800, 600]}))));
+    }
+
+    #[test]
+    fn resize_defer() {
+        let mut w = window(2.0, 2.0);
+        w.set_resize_defer(2);
+        w.set_dpi(1.5).unwrap();
+        assert_eq!(w.size(), Size {width: 400.0, height: 400.0});
+        assert!(!w.has_pending_events());
+        w.inner.push_input(Input::Focus(true));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(true))));
+        assert_eq!(w.poll_event(), None);
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [400.0, 400.0],
+            draw_size: [600, 600],
+        }))));
+        assert_eq!(w.poll_event(), None);
+        assert_eq!(w.last_resize_was_synthetic(), Some(true));
+
+        // The mock window panics when waiting without events, so this would block otherwise.
+        w.set_dpi(2.0).unwrap();
+        w.inner.push_input(Input::Focus(false));
+        assert_eq!(w.wait_event(), input(Input::Focus(false)));
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.wait_event(), input(Input::Resize(ResizeArgs {
+            window_size: [300.0, 300.0],
+            draw_size: [600, 600],
+        })));
+    }
+
+    #[test]
+    fn compensate_real_dpi() {
+        let mut w = window(2.0, 2.0);
+        w.inner.draw_size = Size {width: 1200.0, height: 1200.0};
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        w.set_compensate_real_dpi(true);
+        assert!(w.get_compensate_real_dpi());
+        assert_eq!(w.size(), Size {width: 600.0, height: 600.0});
+        assert_eq!(w.draw_size(), Size {width: 1200.0, height: 1200.0});
+        assert_eq!(w.poll_event(), Some(input(Input::Resize(ResizeArgs {
+            window_size: [600.0, 600.0],
+            draw_size: [1200, 1200],
+        }))));
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([100.0, 50.0])))));
+
+        w.set_dpi(4.0).unwrap();
+        w.poll_event();
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.draw_size(), Size {width: 1200.0, height: 1200.0});
+        w.inner.push_input(Input::Move(Motion::MouseCursor([100.0, 50.0])));
+        assert_eq!(w.poll_event(), Some(input(Input::Move(Motion::MouseCursor([50.0, 25.0])))));
+        assert_eq!(w.map_cursor_physical([100.0, 50.0]), [50.0, 25.0]);
+        assert_eq!(w.unmap_cursor_logical([50.0, 25.0]), [100.0, 50.0]);
+    }
+
+    #[test]
+    fn request_close() {
+        let mut w = window(2.0, 2.0);
+        w.inner.push_input(Input::Focus(true));
+        w.inject_event(Input::Focus(false));
+        w.request_close();
+        assert!(w.should_close());
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(false))));
+        assert_eq!(w.poll_event(), Some(input(Input::Close(input::CloseArgs))));
+        assert_eq!(w.poll_event(), Some(input(Input::Focus(true))));
+    }
+
     #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    fn new_keeps_inner_size() {
+        let w = FakeDpiWindow::new(MockWindow::new([300, 300]), 1.5);
+        assert_eq!(w.inner.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.size(), Size {width: 200.0, height: 200.0});
     }
 }
@@ -0,0 +1,174 @@
+//! A window implementation for testing the mapping logic.
+//!
+//! With the `headless` feature, this is public for tests without a graphics context.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::time::Duration;
+
+use input::{Event, Input};
+#[cfg(feature = "headless")]
+use crate::FakeDpiWindow;
+use window::{
+    AdvancedWindow,
+    BuildFromWindowSettings,
+    OpenGLWindow,
+    Position,
+    ProcAddress,
+    Window,
+    WindowSettings,
+    Size,
+};
+
+/// A window that returns queued events and records calls.
+///
+/// The draw size follows the window size, like on a screen without Hi-DPI support.
+#[derive(Clone, Debug)]
+pub struct MockWindow {
+    /// The window size.
+    pub size: Size,
+    /// The draw size.
+    pub draw_size: Size,
+    /// Events returned when polling.
+    pub events: VecDeque<Event>,
+    /// Whether the window should close.
+    pub should_close: bool,
+    /// The window title.
+    pub title: String,
+    /// Whether the window exits when pressing `Esc`.
+    pub exit_on_esc: bool,
+    /// Whether the window closes automatically.
+    pub automatic_close: bool,
+    /// Whether the cursor is captured.
+    pub capture_cursor: bool,
+    /// Whether the window is visible.
+    pub visible: bool,
+    /// The window position.
+    pub position: Option<Position>,
+    /// The sizes passed to `set_size`.
+    pub set_size_calls: Vec<Size>,
+    /// The positions passed to `set_position`.
+    pub set_position_calls: Vec<Position>,
+    /// The number of calls to `swap_buffers`.
+    pub swap_buffers_calls: usize,
+    /// Whether the OpenGL context is current.
+    pub current: bool,
+}
+
+impl MockWindow {
+    /// Creates a new mock window.
+    pub fn new<S: Into<Size>>(size: S) -> MockWindow {
+        let size = size.into();
+        MockWindow {
+            size,
+            draw_size: size,
+            events: VecDeque::new(),
+            should_close: false,
+            title: String::new(),
+            exit_on_esc: false,
+            automatic_close: true,
+            capture_cursor: false,
+            visible: true,
+            position: Some(Position {x: 0, y: 0}),
+            set_size_calls: vec![],
+            set_position_calls: vec![],
+            swap_buffers_calls: 0,
+            current: false,
+        }
+    }
+
+    /// Queues an input event without time stamp.
+    pub fn push_input(&mut self, e: Input) {
+        self.events.push_back(Event::Input(e, None));
+    }
+}
+
+impl Default for MockWindow {
+    fn default() -> MockWindow {MockWindow::new([800, 600])}
+}
+
+impl BuildFromWindowSettings for MockWindow {
+    fn build_from_window_settings(
+        settings: &WindowSettings
+    ) -> Result<Self, Box<dyn Error + 'static>> {
+        let mut w = MockWindow::new(settings.get_size());
+        w.title = settings.get_title();
+        w.exit_on_esc = settings.get_exit_on_esc();
+        w.automatic_close = settings.get_automatic_close();
+        Ok(w)
+    }
+}
+
+impl Window for MockWindow {
+    fn set_should_close(&mut self, val: bool) {self.should_close = val}
+    fn should_close(&self) -> bool {self.should_close}
+    fn size(&self) -> Size {self.size}
+    fn swap_buffers(&mut self) {self.swap_buffers_calls += 1}
+    fn wait_event(&mut self) -> Event {
+        self.events.pop_front().expect("MockWindow has no queued events")
+    }
+    fn wait_event_timeout(&mut self, _val: Duration) -> Option<Event> {self.events.pop_front()}
+    fn poll_event(&mut self) -> Option<Event> {self.events.pop_front()}
+    fn draw_size(&self) -> Size {self.draw_size}
+}
+
+impl AdvancedWindow for MockWindow {
+    fn get_title(&self) -> String {self.title.clone()}
+    fn set_title(&mut self, val: String) {self.title = val}
+    fn get_exit_on_esc(&self) -> bool {self.exit_on_esc}
+    fn set_exit_on_esc(&mut self, val: bool) {self.exit_on_esc = val}
+    fn get_automatic_close(&self) -> bool {self.automatic_close}
+    fn set_automatic_close(&mut self, val: bool) {self.automatic_close = val}
+    fn set_capture_cursor(&mut self, val: bool) {self.capture_cursor = val}
+    fn show(&mut self) {self.visible = true}
+    fn hide(&mut self) {self.visible = false}
+    fn get_position(&self) -> Option<Position> {self.position}
+    fn set_position<P: Into<Position>>(&mut self, val: P) {
+        let pos = val.into();
+        self.set_position_calls.push(pos);
+        self.position = Some(pos);
+    }
+    fn set_size<S: Into<Size>>(&mut self, val: S) {
+        let size = val.into();
+        self.set_size_calls.push(size);
+        self.size = size;
+        self.draw_size = size;
+    }
+}
+
+impl OpenGLWindow for MockWindow {
+    fn get_proc_address(&mut self, _proc_name: &str) -> ProcAddress {std::ptr::null()}
+    fn is_current(&self) -> bool {self.current}
+    fn make_current(&mut self) {self.current = true}
+}
+
+#[cfg(feature = "headless")]
+impl FakeDpiWindow<MockWindow> {
+    /// Creates a window without a graphics context, for testing the mapping logic.
+    ///
+    /// The size is logical, and the mock window is created with the size multiplied
+    /// by the DPI factor, like when building from settings.
+    ///
+    /// # Panics
+    ///
+    /// If the DPI factor is zero, negative or not finite.
+    pub fn headless<S: Into<Size>>(size: S, dpi: f64) -> FakeDpiWindow<MockWindow> {
+        let mut w = FakeDpiWindow::new(MockWindow::new([0, 0]), dpi);
+        w.inner = MockWindow::new(w.physical_pixels(size.into()));
+        w
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headless() {
+        let mut w = FakeDpiWindow::headless([400, 300], 2.0);
+        assert_eq!(w.size(), Size {width: 400.0, height: 300.0});
+        assert_eq!(w.inner.size, Size {width: 800.0, height: 600.0});
+        w.inject_event(Input::Move(input::Motion::MouseCursor([100.0, 50.0])));
+        assert_eq!(w.poll_event(), Some(Event::Input(Input::Move(input::Motion::MouseCursor([50.0, 25.0])), None)));
+    }
+}
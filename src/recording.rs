@@ -0,0 +1,178 @@
+//! Recording and replaying of event streams.
+//!
+//! A recording starts with a header line containing the format version,
+//! followed by one JSON array `[input, time_stamp]` per line.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use input::{Event, Input, TimeStamp};
+
+use crate::FakeDpiWindow;
+
+/// The header of the current recording format.
+const HEADER: &str = "fake_dpi recording v1";
+
+/// Writes events to a recording file.
+pub(crate) struct Recorder {
+    writer: BufWriter<File>,
+    /// The error that stopped the recording, returned by `stop_recording`.
+    error: Option<io::Error>,
+}
+
+impl Recorder {
+    /// Creates a new recording file.
+    fn create(path: &Path) -> io::Result<Recorder> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", HEADER)?;
+        Ok(Recorder {writer, error: None})
+    }
+
+    /// Appends an event to the recording.
+    ///
+    /// Events that are not input events are skipped.
+    pub(crate) fn write(&mut self, e: &Event) -> io::Result<()> {
+        if let Event::Input(input, ts) = e {
+            serde_json::to_writer(&mut self.writer, &(input, ts))?;
+            writeln!(self.writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads events from a recording file.
+fn read(path: &Path) -> io::Result<VecDeque<Event>> {
+    let mut lines = BufReader::new(File::open(path)?).lines();
+    match lines.next() {
+        Some(Ok(ref header)) if header == HEADER => {}
+        Some(Err(err)) => return Err(err),
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData,
+            format!("Unsupported recording format, expected header `{}`", HEADER))),
+    }
+    let mut events = VecDeque::new();
+    for line in lines {
+        let line = line?;
+        if line.is_empty() {continue}
+        let (input, ts): (Input, Option<TimeStamp>) = serde_json::from_str(&line)?;
+        events.push_back(Event::Input(input, ts));
+    }
+    Ok(events)
+}
+
+impl<W> FakeDpiWindow<W> {
+    /// Wraps an already built window, replaying events from a recording.
+    ///
+    /// Recorded events are already in logical space, so they are returned
+    /// by the polling methods without mapping, instead of events from the inner window.
+    /// When the recording is exhausted, events come from the inner window again.
+    /// Returns an error if the file can not be read or has an unsupported format version.
+    ///
+    /// # Panics
+    ///
+    /// If the DPI factor is zero, negative or not finite.
+    pub fn from_recording<P: AsRef<Path>>(inner: W, dpi: f64, path: P) -> io::Result<FakeDpiWindow<W>> {
+        let mut w = FakeDpiWindow::new(inner, dpi);
+        w.replay = read(path.as_ref())?;
+        Ok(w)
+    }
+
+    /// Starts recording events returned by the polling methods to a file.
+    ///
+    /// The events are recorded in logical space, with their time stamps.
+    /// Any previous recording is stopped, and its error is returned like by `stop_recording`,
+    /// without starting a new recording.
+    /// If writing fails while polling, the recording stops, and the error is returned
+    /// by `stop_recording`.
+    pub fn start_recording<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.stop_recording()?;
+        self.hooks.recorder = Some(Recorder::create(path.as_ref())?);
+        Ok(())
+    }
+
+    /// Stops recording events, flushing the file.
+    ///
+    /// Returns an error if flushing fails,
+    /// or the error that stopped the recording if writing failed while polling.
+    pub fn stop_recording(&mut self) -> io::Result<()> {
+        match self.hooks.recorder.take() {
+            Some(Recorder {error: Some(err), ..}) => Err(err),
+            Some(mut recorder) => recorder.writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns `true` while recording events.
+    ///
+    /// This is `false` after writing failed, until the recording is stopped or restarted.
+    pub fn is_recording(&self) -> bool {
+        self.hooks.recorder.as_ref().is_some_and(|recorder| recorder.error.is_none())
+    }
+
+    /// Records an event, if recording.
+    pub(crate) fn record(&mut self, e: &Event) {
+        if let Some(ref mut recorder) = self.hooks.recorder {
+            if recorder.error.is_some() {return}
+            if let Err(err) = recorder.write(e) {recorder.error = Some(err)}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockWindow;
+    use input::Motion;
+    use window::Window;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("fake_dpi_{}_{}.txt", name, std::process::id()))
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = temp_path("round_trip");
+        let mut w = FakeDpiWindow::new(MockWindow::new([600, 600]), 2.0);
+        w.start_recording(&path).unwrap();
+        assert!(w.is_recording());
+        w.inject_event(Input::Move(Motion::MouseCursor([30.0, 10.0])));
+        w.inject_event(Input::Text("é".into()));
+        w.inner.events.push_back(Event::Input(Input::Focus(true), Some(42)));
+        let recorded: Vec<_> = std::iter::from_fn(|| w.poll_event()).collect();
+        w.stop_recording().unwrap();
+        assert_eq!(recorded.len(), 3);
+
+        let mut w = FakeDpiWindow::from_recording(MockWindow::new([600, 600]), 2.0, &path).unwrap();
+        w.inner.events.push_back(Event::Input(Input::Focus(false), None));
+        let replayed: Vec<_> = std::iter::from_fn(|| w.poll_event()).collect();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&replayed[..3], &recorded[..]);
+        assert_eq!(replayed[2], Event::Input(Input::Focus(true), Some(42)));
+        assert_eq!(replayed[3], Event::Input(Input::Focus(false), None));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn write_error() {
+        let mut w = FakeDpiWindow::new(MockWindow::new([600, 600]), 2.0);
+        w.start_recording("/dev/full").unwrap();
+        let text = "x".repeat(1000);
+        for _ in 0..100 {
+            w.inject_event(Input::Text(text.clone()));
+            assert!(w.poll_event().is_some());
+        }
+        assert!(!w.is_recording());
+        assert!(w.stop_recording().is_err());
+        assert!(w.stop_recording().is_ok());
+    }
+
+    #[test]
+    fn reject_unknown_version() {
+        let path = temp_path("unknown_version");
+        std::fs::write(&path, "fake_dpi recording v0\n").unwrap();
+        let res = FakeDpiWindow::from_recording(MockWindow::new([600, 600]), 2.0, &path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(res.err().unwrap().kind(), io::ErrorKind::InvalidData);
+    }
+}
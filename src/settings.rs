@@ -0,0 +1,276 @@
+//! Window settings with fake DPI options.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+
+use window::{AdvancedWindow, BuildFromWindowSettings, Size, WindowSettings};
+
+use crate::{validate_dpi, FakeDpiWindow, DEFAULT_DPI};
+
+/// The environment variable that overrides the default initial DPI factor.
+///
+/// The value must parse as a positive finite number, e.g. `FAKE_DPI=1.5`.
+/// With an invalid value, building fails with `InvalidDpiEnvVar`,
+/// unless the DPI factor is set explicitly.
+pub const DPI_ENV_VAR: &str = "FAKE_DPI";
+
+/// An error returned when building with an invalid `FAKE_DPI` environment variable.
+///
+/// Stores the value of the environment variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidDpiEnvVar(pub String);
+
+impl fmt::Display for InvalidDpiEnvVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid `{}` value `{}`, expected a positive finite number", DPI_ENV_VAR, self.0)
+    }
+}
+
+impl Error for InvalidDpiEnvVar {}
+
+/// Implemented by settings that control the initial fake DPI.
+pub trait FakeDpiSettings {
+    /// Sets the initial DPI factor.
+    fn set_fake_dpi(&mut self, dpi: f64) -> &mut Self;
+    /// Gets the initial DPI factor.
+    fn get_fake_dpi(&self) -> f64;
+    /// Sets the initial DPI factor.
+    ///
+    /// This method moves the current settings value and returns it.
+    fn fake_dpi(mut self, dpi: f64) -> Self where Self: Sized {
+        self.set_fake_dpi(dpi);
+        self
+    }
+}
+
+/// Controls how the size of the window settings is interpreted when building.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum BuildSizeMode {
+    /// The size is logical, and the inner window is created with the size
+    /// multiplied by the DPI factor, e.g. `800x600` opens a `1600x1200` window at `2.0`.
+    #[default]
+    InflatePhysical,
+    /// The size is physical, and the inner window is created with it unchanged,
+    /// e.g. `800x600` opens a `800x600` window with logical size `400x300` at `2.0`.
+    KeepPhysical,
+}
+
+/// Wraps window settings to build a window with fake DPI options.
+///
+/// By default, the size of the window settings is the logical size seen by the application.
+#[derive(Clone, Debug)]
+pub struct FakeDpiWindowSettings {
+    /// The settings of the inner window.
+    pub settings: WindowSettings,
+    fake_dpi: f64,
+    /// The invalid value of the environment variable, until the DPI factor is set explicitly.
+    env_error: Option<InvalidDpiEnvVar>,
+    initial_resize: bool,
+    tag_title: bool,
+    start_hidden: bool,
+    size_mode: BuildSizeMode,
+}
+
+impl FakeDpiWindowSettings {
+    /// Creates new settings with default DPI factor `2.0`.
+    ///
+    /// The default can be overridden with the `FAKE_DPI` environment variable.
+    /// If its value is invalid, building returns an `InvalidDpiEnvVar` error,
+    /// unless the DPI factor is set explicitly.
+    pub fn new(settings: WindowSettings) -> FakeDpiWindowSettings {
+        let (fake_dpi, env_error) = match env_dpi() {
+            Ok(dpi) => (dpi.unwrap_or(DEFAULT_DPI), None),
+            Err(err) => (DEFAULT_DPI, Some(err)),
+        };
+        FakeDpiWindowSettings {
+            settings,
+            fake_dpi,
+            env_error,
+            initial_resize: false,
+            tag_title: false,
+            start_hidden: false,
+            size_mode: BuildSizeMode::InflatePhysical,
+        }
+    }
+
+    /// Gets whether the first polled event is a resize event.
+    pub fn get_initial_resize(&self) -> bool {self.initial_resize}
+
+    /// Sets whether the first polled event is a resize event.
+    ///
+    /// The resize event has the logical window size and draw size
+    /// at the configured DPI factor, such that applications learn their
+    /// drawing size on start-up. If the DPI changes before the first poll,
+    /// a single resize event with the latest size is delivered.
+    /// By default, this is off.
+    pub fn set_initial_resize(&mut self, val: bool) {self.initial_resize = val}
+
+    /// Sets whether the first polled event is a resize event.
+    ///
+    /// This method moves the current settings value and returns it.
+    pub fn initial_resize(mut self, val: bool) -> Self {
+        self.set_initial_resize(val);
+        self
+    }
+
+    /// Gets whether the window title is tagged with the DPI factor.
+    pub fn get_tag_title(&self) -> bool {self.tag_title}
+
+    /// Sets whether the window title is tagged with the DPI factor.
+    ///
+    /// When enabled, a suffix like ` (fake dpi 2x)` is appended to the title,
+    /// which helps to tell apart windows running side by side at different DPI.
+    /// By default, this is off.
+    pub fn set_tag_title(&mut self, val: bool) {self.tag_title = val}
+
+    /// Sets whether the window title is tagged with the DPI factor.
+    ///
+    /// This method moves the current settings value and returns it.
+    pub fn tag_title(mut self, val: bool) -> Self {
+        self.set_tag_title(val);
+        self
+    }
+
+    /// Gets whether the window is hidden right after building it.
+    pub fn get_start_hidden(&self) -> bool {self.start_hidden}
+
+    /// Sets whether the window is hidden right after building it.
+    ///
+    /// This simulates a window that starts hidden, to test initialization logic
+    /// that runs before the first `show`. The wrapper reports `is_visible() == false`
+    /// until the window is shown. By default, this is off.
+    pub fn set_start_hidden(&mut self, val: bool) {self.start_hidden = val}
+
+    /// Sets whether the window is hidden right after building it.
+    ///
+    /// This method moves the current settings value and returns it.
+    pub fn start_hidden(mut self, val: bool) -> Self {
+        self.set_start_hidden(val);
+        self
+    }
+
+    /// Gets how the size of the window settings is interpreted.
+    pub fn get_size_mode(&self) -> BuildSizeMode {self.size_mode}
+
+    /// Sets how the size of the window settings is interpreted.
+    ///
+    /// By default, the size is logical and the inner window is inflated.
+    pub fn set_size_mode(&mut self, val: BuildSizeMode) {self.size_mode = val}
+
+    /// Sets how the size of the window settings is interpreted.
+    ///
+    /// This method moves the current settings value and returns it.
+    pub fn size_mode(mut self, val: BuildSizeMode) -> Self {
+        self.set_size_mode(val);
+        self
+    }
+
+    /// Builds window from the given settings.
+    ///
+    /// The inner window is created with the logical size multiplied by the DPI factor,
+    /// or with the size unchanged when the size mode keeps the physical size,
+    /// and hidden right after if enabled.
+    /// Returns an error if the DPI factor is zero, negative or not finite.
+    pub fn build<W: BuildFromWindowSettings + AdvancedWindow>(
+        &self
+    ) -> Result<FakeDpiWindow<W>, Box<dyn Error + 'static>> {
+        let mut w: FakeDpiWindow<W> = self.build_window()?;
+        if self.start_hidden {w.hide()}
+        Ok(w)
+    }
+
+    /// Builds window from the given settings, without hiding it.
+    pub(crate) fn build_window<W: BuildFromWindowSettings>(
+        &self
+    ) -> Result<FakeDpiWindow<W>, Box<dyn Error + 'static>> {
+        if let Some(ref err) = self.env_error {return Err(Box::new(err.clone()))}
+        let dpi = validate_dpi(self.fake_dpi)?;
+        let mut settings = self.settings.clone();
+        if self.size_mode == BuildSizeMode::InflatePhysical {
+            let size = settings.get_size();
+            settings.set_size(Size {
+                width: size.width * dpi,
+                height: size.height * dpi,
+            });
+        }
+        if self.tag_title {
+            let title = settings.get_title();
+            settings.set_title(title + &title_suffix(dpi));
+        }
+        let mut w = FakeDpiWindow::new(settings.build()?, dpi);
+        w.resize_pending = self.initial_resize;
+        Ok(w)
+    }
+}
+
+impl From<WindowSettings> for FakeDpiWindowSettings {
+    fn from(settings: WindowSettings) -> FakeDpiWindowSettings {
+        FakeDpiWindowSettings::new(settings)
+    }
+}
+
+impl FakeDpiSettings for FakeDpiWindowSettings {
+    fn set_fake_dpi(&mut self, dpi: f64) -> &mut Self {
+        self.fake_dpi = dpi;
+        self.env_error = None;
+        self
+    }
+    fn get_fake_dpi(&self) -> f64 {self.fake_dpi}
+}
+
+/// Returns the title suffix for a DPI factor.
+pub(crate) fn title_suffix(dpi: f64) -> String {
+    format!(" (fake dpi {}x)", dpi)
+}
+
+/// Returns a title without the suffix for a DPI factor, if any.
+pub(crate) fn strip_title_suffix(title: &str) -> &str {
+    match title.rfind(" (fake dpi ") {
+        Some(i) if title.ends_with("x)") => &title[..i],
+        _ => title,
+    }
+}
+
+/// Reads the DPI factor from the environment, if set.
+fn env_dpi() -> Result<Option<f64>, InvalidDpiEnvVar> {
+    let val = match env::var_os(DPI_ENV_VAR) {
+        Some(val) => val,
+        None => return Ok(None),
+    };
+    match val.to_str().and_then(parse_dpi) {
+        Some(dpi) => Ok(Some(dpi)),
+        None => Err(InvalidDpiEnvVar(val.to_string_lossy().into_owned())),
+    }
+}
+
+/// Parses a positive finite DPI factor.
+fn parse_dpi(val: &str) -> Option<f64> {
+    val.trim().parse::<f64>().ok().and_then(|dpi| validate_dpi(dpi).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suffix() {
+        assert_eq!(title_suffix(2.0), " (fake dpi 2x)");
+        assert_eq!(title_suffix(1.25), " (fake dpi 1.25x)");
+        assert_eq!(strip_title_suffix("test (fake dpi 1.25x)"), "test");
+        assert_eq!(strip_title_suffix("test"), "test");
+        assert_eq!(strip_title_suffix("a (fake dpi 2x) (fake dpi 3x)"), "a (fake dpi 2x)");
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_dpi("1.5"), Some(1.5));
+        assert_eq!(parse_dpi(" 3 "), Some(3.0));
+        assert_eq!(parse_dpi("0"), None);
+        assert_eq!(parse_dpi("-2.0"), None);
+        assert_eq!(parse_dpi("inf"), None);
+        assert_eq!(parse_dpi("NaN"), None);
+        assert_eq!(parse_dpi("two"), None);
+        assert_eq!(parse_dpi(""), None);
+    }
+}
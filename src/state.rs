@@ -0,0 +1,232 @@
+//! Snapshots of the state of a fake DPI window.
+
+use std::time::Duration;
+
+use input::Key;
+use window::{Size, Window};
+
+use crate::{CoordinateSpace, FakeDpiWindow, Monitor, Orientation, RoundingMode, ScaleMask};
+
+/// Stores the DPI factors and configuration of a fake DPI window.
+///
+/// Created by `FakeDpiWindow::snapshot_state` and applied with `restore_state`,
+/// e.g. to try several configurations in a test from a common baseline.
+/// This does not include the inner window, pending events and event statistics.
+/// Callbacks, loggers, transforms, hooks and the custom coordinate mapper can not be copied,
+/// so they are not restored, and neither is whether the title is tagged with the DPI factor.
+#[derive(Clone, Debug)]
+pub struct State {
+    dpi: [f64; 2],
+    input_dpi: Option<f64>,
+    dpi_range: [f64; 2],
+    resize_throttle: Duration,
+    resize_defer: u32,
+    resize_debounce: Duration,
+    input_latency: Duration,
+    dpi_threshold: Option<Size>,
+    compensate_real_dpi: bool,
+    detect_size_changes: bool,
+    enabled: bool,
+    scale_scroll: bool,
+    scale_relative_motion: bool,
+    scale_mask: ScaleMask,
+    check_size_ratio: bool,
+    rounding: RoundingMode,
+    cursor_space: CoordinateSpace,
+    clamp_cursor: bool,
+    epsilon: f64,
+    flip_y: bool,
+    orientation: Orientation,
+    report_physical_size: bool,
+    strict: bool,
+    size_check_tolerance: Option<f64>,
+    origin: [f64; 2],
+    letterbox: Option<Size>,
+    paired_scroll: Option<f64>,
+    scroll_step: Option<f64>,
+    derive_relative_motion: bool,
+    collect_event_stats: bool,
+    dpi_hotkey: Option<Key>,
+    dpi_hotkey_step: f64,
+    swallow_dpi_hotkey: bool,
+    auto_close_after: Option<u32>,
+    monitors: Vec<f64>,
+    active_monitor: Option<usize>,
+    monitor_layout: Vec<Monitor>,
+    virtual_desktop: bool,
+    dpi_presets: Vec<f64>,
+    dpi_steps: Vec<f64>,
+    paused: bool,
+    cursor_quantum: Option<f64>,
+    input_precision: Option<f64>,
+    #[cfg(feature = "random")]
+    dpi_jitter: f64,
+}
+
+impl State {
+    /// Returns the horizontal and vertical DPI factor.
+    pub fn dpi(&self) -> [f64; 2] {self.dpi}
+
+    /// Returns the rounding mode for logical coordinates.
+    pub fn rounding(&self) -> RoundingMode {self.rounding}
+}
+
+impl<W: Window> FakeDpiWindow<W> {
+    /// Takes a snapshot of the DPI factors and configuration.
+    pub fn snapshot_state(&self) -> State {
+        State {
+            dpi: self.dpi_xy(),
+            input_dpi: self.input_dpi,
+            dpi_range: self.dpi_range(),
+            resize_throttle: self.resize_throttle,
+            resize_defer: self.resize_defer,
+            resize_debounce: self.resize_debounce,
+            input_latency: self.input_latency,
+            dpi_threshold: self.dpi_threshold,
+            compensate_real_dpi: self.compensate_real_dpi,
+            detect_size_changes: self.get_detect_size_changes(),
+            enabled: self.enabled,
+            scale_scroll: self.scale_scroll,
+            scale_relative_motion: self.scale_relative_motion,
+            scale_mask: self.scale_mask,
+            check_size_ratio: self.check_size_ratio,
+            rounding: self.rounding,
+            cursor_space: self.cursor_space,
+            clamp_cursor: self.clamp_cursor,
+            epsilon: self.epsilon,
+            flip_y: self.flip_y,
+            orientation: self.orientation,
+            report_physical_size: self.report_physical_size,
+            strict: self.strict,
+            size_check_tolerance: self.size_check_tolerance,
+            origin: self.origin,
+            letterbox: self.letterbox,
+            paired_scroll: self.paired_scroll,
+            scroll_step: self.scroll_step,
+            derive_relative_motion: self.derive_relative_motion,
+            collect_event_stats: self.get_collect_event_stats(),
+            dpi_hotkey: self.dpi_hotkey,
+            dpi_hotkey_step: self.dpi_hotkey_step,
+            swallow_dpi_hotkey: self.swallow_dpi_hotkey,
+            auto_close_after: self.auto_close_after,
+            monitors: self.monitors.clone(),
+            active_monitor: self.active_monitor,
+            monitor_layout: self.monitor_layout.clone(),
+            virtual_desktop: self.virtual_desktop,
+            dpi_presets: self.dpi_presets.clone(),
+            dpi_steps: self.dpi_steps.clone(),
+            paused: self.paused,
+            cursor_quantum: self.cursor_quantum,
+            input_precision: self.input_precision,
+            #[cfg(feature = "random")]
+            dpi_jitter: self.dpi_jitter,
+        }
+    }
+
+    /// Restores the DPI factors and configuration from a snapshot.
+    ///
+    /// Options are restored like with their setters, so a resize event is emitted
+    /// if the logical size changes, and the DPI change callback is called if the factors change.
+    /// Running DPI animations are stopped. Callbacks, loggers, transforms and hooks
+    /// are kept as they are, see `State`.
+    pub fn restore_state(&mut self, state: State) {
+        self.animation = None;
+        self.min_dpi = state.dpi_range[0];
+        self.max_dpi = state.dpi_range[1];
+        if state.dpi != self.dpi_xy() {self.apply_dpi(state.dpi)}
+        self.input_dpi = state.input_dpi;
+        self.resize_throttle = state.resize_throttle;
+        self.resize_defer = state.resize_defer;
+        if state.resize_debounce != self.resize_debounce {self.set_resize_debounce(state.resize_debounce)}
+        self.input_latency = state.input_latency;
+        self.set_dpi_threshold(state.dpi_threshold);
+        self.set_compensate_real_dpi(state.compensate_real_dpi);
+        if state.detect_size_changes != self.get_detect_size_changes() {
+            self.set_detect_size_changes(state.detect_size_changes);
+        }
+        self.set_enabled(state.enabled);
+        self.scale_scroll = state.scale_scroll;
+        self.scale_relative_motion = state.scale_relative_motion;
+        self.scale_mask = state.scale_mask;
+        self.check_size_ratio = state.check_size_ratio;
+        self.rounding = state.rounding;
+        self.cursor_space = state.cursor_space;
+        self.clamp_cursor = state.clamp_cursor;
+        self.epsilon = state.epsilon;
+        self.flip_y = state.flip_y;
+        self.set_orientation(state.orientation);
+        self.set_report_physical_size(state.report_physical_size);
+        self.strict = state.strict;
+        self.size_check_tolerance = state.size_check_tolerance;
+        self.origin = state.origin;
+        self.letterbox = state.letterbox;
+        self.paired_scroll = state.paired_scroll;
+        self.scroll_step = state.scroll_step;
+        self.derive_relative_motion = state.derive_relative_motion;
+        self.set_collect_event_stats(state.collect_event_stats);
+        self.dpi_hotkey = state.dpi_hotkey;
+        self.dpi_hotkey_step = state.dpi_hotkey_step;
+        self.swallow_dpi_hotkey = state.swallow_dpi_hotkey;
+        self.auto_close_after = state.auto_close_after;
+        self.monitors = state.monitors;
+        self.active_monitor = state.active_monitor;
+        self.monitor_layout = state.monitor_layout;
+        self.virtual_desktop = state.virtual_desktop;
+        self.dpi_presets = state.dpi_presets;
+        self.last_preset = None;
+        self.dpi_steps = state.dpi_steps;
+        self.paused = state.paused;
+        self.cursor_quantum = state.cursor_quantum;
+        self.input_precision = state.input_precision;
+        #[cfg(feature = "random")]
+        {
+            self.dpi_jitter = state.dpi_jitter;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockWindow;
+    use input::{Event, Input, Motion, ResizeArgs};
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut w = FakeDpiWindow::new(MockWindow::new([600, 600]), 2.0);
+        let baseline = w.snapshot_state();
+        assert_eq!(baseline.dpi(), [2.0, 2.0]);
+        assert_eq!(baseline.rounding(), RoundingMode::None);
+
+        w.set_dpi(1.5).unwrap();
+        w.set_rounding(RoundingMode::Floor);
+        w.set_event_transform(Some(Box::new(|_| Input::Focus(true))));
+        assert_eq!(w.snapshot_state().dpi(), [1.5, 1.5]);
+        while w.poll_event().is_some() {}
+
+        w.restore_state(baseline.clone());
+        assert_eq!(w.dpi(), 2.0);
+        assert_eq!(w.get_rounding(), RoundingMode::None);
+        assert_eq!(w.size(), Size {width: 300.0, height: 300.0});
+        assert_eq!(w.poll_event(), Some(Event::Input(Input::Resize(ResizeArgs {
+            window_size: [300.0, 300.0],
+            draw_size: [600, 600],
+        }), None)));
+        // The transform is kept, since hooks are not restored.
+        w.inner.push_input(Input::Focus(false));
+        assert_eq!(w.poll_event(), Some(Event::Input(Input::Focus(true), None)));
+
+        w.set_event_transform(None);
+        w.set_rounding(RoundingMode::Ceil);
+        w.restore_state(baseline);
+        assert_eq!(w.get_rounding(), RoundingMode::None);
+        assert_eq!(w.poll_event(), None);
+        w.inner.push_input(Input::Move(Motion::MouseCursor([101.0, 51.0])));
+        assert_eq!(w.poll_event(), Some(Event::Input(Input::Move(Motion::MouseCursor([50.5, 25.5])), None)));
+        w.set_dpi(3.0).unwrap();
+        assert_eq!(w.poll_event(), Some(Event::Input(Input::Resize(ResizeArgs {
+            window_size: [200.0, 200.0],
+            draw_size: [600, 600],
+        }), None)));
+    }
+}
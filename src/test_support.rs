@@ -0,0 +1,83 @@
+//! Assertions for downstream tests, with the `test-support` feature.
+
+use window::{Size, Window};
+
+use crate::FakeDpiWindow;
+
+/// The smallest tolerance of the assertions, for rounding errors of the DPI math.
+const MIN_TOLERANCE: f64 = 1e-9;
+
+impl<W: Window> FakeDpiWindow<W> {
+    /// Asserts that `size()` equals an expected logical size.
+    ///
+    /// The sizes are compared within the epsilon, or `1e-9` if the epsilon is smaller.
+    ///
+    /// # Panics
+    ///
+    /// If the sizes differ, with both sizes, the physical size and the DPI factors in the message.
+    #[track_caller]
+    pub fn assert_logical_size(&self, expected: Size) {
+        let actual = self.size();
+        if !close([actual.width, actual.height], [expected.width, expected.height], self.tolerance()) {
+            let physical = self.physical_size();
+            panic!("logical size mismatch\n  expected: {}x{}\n    actual: {}x{}\n  physical: {}x{} at dpi {}x{}",
+                   expected.width, expected.height, actual.width, actual.height,
+                   physical.width, physical.height, self.dpi_x(), self.dpi_y());
+        }
+    }
+
+    /// Asserts that a physical mouse cursor position maps to an expected logical position.
+    ///
+    /// The position is mapped with `map_cursor_physical`,
+    /// and compared within the epsilon, or `1e-9` if the epsilon is smaller.
+    ///
+    /// # Panics
+    ///
+    /// If the positions differ, with both positions and the DPI factors in the message.
+    #[track_caller]
+    pub fn assert_scales_to(&self, physical: [f64; 2], expected: [f64; 2]) {
+        let actual = self.map_cursor_physical(physical);
+        if !close(actual, expected, self.tolerance()) {
+            panic!("cursor mapping mismatch for physical {:?}\n  expected: {:?}\n    actual: {:?}\n  \
+                    at dpi {}x{}",
+                   physical, expected, actual, self.dpi_x(), self.dpi_y());
+        }
+    }
+
+    /// Returns the tolerance of the assertions.
+    fn tolerance(&self) -> f64 {self.epsilon().max(MIN_TOLERANCE)}
+}
+
+/// Returns whether two points are within a tolerance.
+fn close(a: [f64; 2], b: [f64; 2], tolerance: f64) -> bool {
+    (a[0] - b[0]).abs() <= tolerance && (a[1] - b[1]).abs() <= tolerance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockWindow;
+
+    #[test]
+    fn assertions() {
+        let mut w = FakeDpiWindow::new(MockWindow::new([600, 300]), 1.5);
+        w.assert_logical_size(Size {width: 400.0, height: 200.0});
+        w.assert_scales_to([30.0, 0.3], [20.0, 0.2]);
+        w.set_epsilon(0.5);
+        w.assert_logical_size(Size {width: 400.4, height: 200.0});
+    }
+
+    #[test]
+    #[should_panic(expected = "expected: 400x201\n    actual: 400x200\n  physical: 600x300 at dpi 1.5x1.5")]
+    fn logical_size_mismatch() {
+        let w = FakeDpiWindow::new(MockWindow::new([600, 300]), 1.5);
+        w.assert_logical_size(Size {width: 400.0, height: 201.0});
+    }
+
+    #[test]
+    #[should_panic(expected = "physical [30.0, 0.0]\n  expected: [15.0, 0.0]\n    actual: [20.0, 0.0]")]
+    fn cursor_mapping_mismatch() {
+        let w = FakeDpiWindow::new(MockWindow::new([600, 300]), 1.5);
+        w.assert_scales_to([30.0, 0.0], [15.0, 0.0]);
+    }
+}